@@ -0,0 +1,163 @@
+//! 2021 day 1: Sonar Sweep.
+
+use advent_input_parser::{explain, AdventError};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// Parses one sonar depth per line.
+pub fn parse_depths(lines: &[String]) -> Result<Vec<usize>, AdventError> {
+    lines
+        .iter()
+        .map(|value| {
+            value.parse::<usize>().map_err(|_| {
+                AdventError::InvalidInput(format!("Expected an unsigned integer, got {:?}", value))
+            })
+        })
+        .collect()
+}
+
+/// Count the number of times the depth increases.
+/// E.g. [102, 100, 105] => 1 increase
+///      [100, 101, 102, 103] => 3 increases
+pub fn count_depth_increases(depths: &[usize]) -> Result<usize, AdventError> {
+    let (mut last_depth, depths) = depths.split_first().ok_or(AdventError::EmptyInput)?;
+    let mut number_of_increases = 0;
+
+    for depth in depths {
+        if depth > last_depth {
+            number_of_increases += 1;
+        }
+        explain!(
+            "{} -> {}: {}",
+            last_depth,
+            depth,
+            change(*last_depth, *depth)
+        );
+        last_depth = depth;
+    }
+
+    Ok(number_of_increases)
+}
+
+/// Count the number of times the depth increases in a sliding window of size 3
+/// Groups of 3 depths are added together and the sliding windows are then compared in order.
+/// E.g. [100, 101, 102, 103, 104, 105] => 4 increases in windows [100, 101, 102], [101, 102, 103], etc.
+pub fn count_sliding_window_depth_increases(depths: &[usize]) -> Result<usize, AdventError> {
+    // Queue that will contain at most 3 values, and will track the current depth windows.
+    let mut depth_windows: VecDeque<usize> = VecDeque::new();
+
+    let mut number_of_increases = 0;
+    let mut last_window: Option<usize> = None;
+
+    for &depth in depths {
+        // Add depth to all currently existing windows
+        for window in depth_windows.iter_mut() {
+            *window = window.checked_add(depth).ok_or_else(|| {
+                AdventError::NoSolution("A window's total depth doesn't fit in a usize".into())
+            })?;
+        }
+
+        // Add the current depth to the end of our queue
+        depth_windows.push_back(depth);
+
+        // If the queue is now size = 3 [window size], pop the first value,
+        // which is our "last window" to compare against.
+        if depth_windows.len() == 3 {
+            let new_last_window = depth_windows.pop_front().unwrap();
+
+            // If we have a last window to compare against, do so.
+            if let Some(value) = last_window {
+                if new_last_window > value {
+                    number_of_increases += 1;
+                }
+                explain!(
+                    "Window {} -> {}: {}",
+                    value,
+                    new_last_window,
+                    change(value, new_last_window)
+                );
+            }
+
+            last_window = Some(new_last_window);
+        }
+    }
+
+    Ok(number_of_increases)
+}
+
+// Describes a change in depth, for --explain.
+fn change(from: usize, to: usize) -> &'static str {
+    match to.cmp(&from) {
+        Ordering::Greater => "increased",
+        Ordering::Less => "decreased",
+        Ordering::Equal => "no change",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{count_depth_increases, count_sliding_window_depth_increases, parse_depths};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_sample() {
+        let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert_eq!(count_depth_increases(&depths).unwrap(), 7);
+        assert_eq!(count_sliding_window_depth_increases(&depths).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_empty_input_is_an_error() {
+        assert!(count_depth_increases(&[]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_depth_is_an_error() {
+        assert!(parse_depths(&["12".to_string(), "-3".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_window_overflow_is_an_error() {
+        assert!(count_sliding_window_depth_increases(&[usize::MAX, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn test_explains_each_comparison() {
+        let depths = [100, 102, 101, 101];
+        let (_, explanations) = capture_explanations(|| count_depth_increases(&depths));
+        assert_eq!(
+            explanations,
+            vec![
+                "100 -> 102: increased",
+                "102 -> 101: decreased",
+                "101 -> 101: no change",
+            ]
+        );
+
+        let (_, explanations) =
+            capture_explanations(|| count_sliding_window_depth_increases(&depths));
+        assert_eq!(explanations, vec!["Window 303 -> 304: increased"]);
+    }
+
+    // Naive reference: sum every window up front, then count the sums that are larger
+    // than the one before. Part 1 is the same with windows of 1.
+    fn naive_increases(depths: &[usize], window_size: usize) -> usize {
+        let sums: Vec<usize> = depths
+            .windows(window_size)
+            .map(|window| window.iter().sum())
+            .collect();
+        sums.windows(2).filter(|pair| pair[1] > pair[0]).count()
+    }
+
+    proptest! {
+        #[test]
+        fn test_increases_match_naive_windows(depths in prop::collection::vec(0..1000usize, 1..50)) {
+            prop_assert_eq!(count_depth_increases(&depths).unwrap(), naive_increases(&depths, 1));
+            prop_assert_eq!(
+                count_sliding_window_depth_increases(&depths).unwrap(),
+                naive_increases(&depths, 3)
+            );
+        }
+    }
+}
@@ -1,77 +1,42 @@
-use advent_input_parser::parse_by_line;
-use std::collections::VecDeque;
-use std::io;
+//! Solves 2021 day 1: Sonar Sweep. Reads the puzzle input from stdin and prints both answers.
 
-fn main() {
-    let parsed_input = parse_by_line(io::stdin().lock());
-
-    let mut depths: VecDeque<usize> = VecDeque::new();
-    for value in parsed_input {
-        depths.push_back(
-            value
-                .parse::<usize>()
-                .expect("Expected an unsigned integer!"),
-        );
-    }
-
-    let part_1_answer = count_depth_increases(depths.clone());
-    println!("Part 1 answer: {:?}", part_1_answer);
+use advent_2021_day01::{
+    count_depth_increases, count_sliding_window_depth_increases, parse_depths,
+};
+use advent_input_parser::{
+    init_explain, init_logging, init_timing, parse_by_line, report_timing, time_span, AdventError,
+};
+use std::{io, process};
 
-    let part_2_answer = count_sliding_window_depth_increases(depths.clone());
-    println!("Part 2 answer: {:?}", part_2_answer);
-}
+fn main() {
+    init_logging();
+    init_timing();
+    init_explain();
 
-// Count the number of times the depth increases.
-// E.g. [102, 100, 105] => 1 increase
-//      [100, 101, 102, 103] => 3 increases
-fn count_depth_increases(mut depths: VecDeque<usize>) -> usize {
-    let mut last_depth = depths.pop_front().unwrap();
-    let mut number_of_increases = 0;
+    let result = run();
+    report_timing();
 
-    for depth in depths {
-        if depth > last_depth {
-            number_of_increases += 1;
-        }
-        last_depth = depth;
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
     }
-
-    number_of_increases
 }
 
-// Count the number of times the depth increases in a sliding window of size 3
-// Groups of 3 depths are added together and the sliding windows are then compared in order.
-// E.g. [100, 101, 102, 103, 104, 105] => 4 increases in windows [100, 101, 102], [101, 102, 103], etc.
-fn count_sliding_window_depth_increases(depths: VecDeque<usize>) -> usize {
-    // Queue that will contain at most 3 values, and will track the current depth windows.
-    let mut depth_windows: VecDeque<usize> = VecDeque::new();
-
-    let mut number_of_increases = 0;
-    let mut last_window: Option<usize> = None;
-
-    for depth in depths {
-        // Add depth to all currently existing windows
-        for window in depth_windows.iter_mut() {
-            *window += depth;
-        }
-
-        // Add the current depth to the end of our queue
-        depth_windows.push_back(depth);
+fn run() -> Result<(), AdventError> {
+    let parse_span = time_span("parse");
+    let parsed_input = parse_by_line(io::stdin().lock())?;
+    let depths = parse_depths(&parsed_input)?;
+    drop(parse_span);
 
-        // If the queue is now size = 3 [window size], pop the first value,
-        // which is our "last window" to compare against.
-        if depth_windows.len() == 3 {
-            let new_last_window = depth_windows.pop_front().unwrap();
-
-            // If we have a last window to compare against, do so.
-            if let Some(value) = last_window {
-                if new_last_window > value {
-                    number_of_increases += 1;
-                }
-            }
+    let part_1_span = time_span("part 1");
+    let part_1_answer = count_depth_increases(&depths)?;
+    println!("Part 1 answer: {:?}", part_1_answer);
+    drop(part_1_span);
 
-            last_window = Some(new_last_window);
-        }
-    }
+    let part_2_span = time_span("part 2");
+    let part_2_answer = count_sliding_window_depth_increases(&depths)?;
+    println!("Part 2 answer: {:?}", part_2_answer);
+    drop(part_2_span);
 
-    number_of_increases
+    Ok(())
 }
@@ -0,0 +1,299 @@
+//! 2021 day 2: Dive!
+
+use advent_input_parser::{explain, AdventError};
+
+/// A single line of the planned course.
+#[derive(Debug)]
+pub enum Command {
+    /// Move forward by the given amount.
+    Forward(isize),
+    /// Go down by the given amount (or increase the aim, in part 2).
+    Down(isize),
+    /// Go up by the given amount (or decrease the aim, in part 2).
+    Up(isize),
+}
+
+/// I like to think the submarine is piloted by cats
+#[derive(Debug)]
+pub struct Meowmarine {
+    /// How far forward the submarine has moved.
+    pub horizontal: isize,
+    /// How far below the surface the submarine is.
+    pub depth: isize,
+    /// Only used in part 2. Each forward move also dives by aim * distance.
+    pub aim: isize,
+}
+
+impl Meowmarine {
+    /// A submarine at the surface, pointing straight ahead.
+    pub fn new() -> Meowmarine {
+        Meowmarine {
+            horizontal: 0,
+            depth: 0,
+            aim: 0,
+        }
+    }
+
+    /// Used for part 1 of the puzzle
+    pub fn command_1(self, command: &Command) -> Result<Meowmarine, AdventError> {
+        Ok(match command {
+            Command::Forward(amt) => Meowmarine {
+                horizontal: self
+                    .horizontal
+                    .checked_add(*amt)
+                    .ok_or_else(position_overflow)?,
+                ..self
+            },
+            // Note that Down means depth increases
+            Command::Down(amt) => Meowmarine {
+                depth: self.depth.checked_add(*amt).ok_or_else(position_overflow)?,
+                ..self
+            },
+            Command::Up(amt) => Meowmarine {
+                depth: self.depth.checked_sub(*amt).ok_or_else(position_overflow)?,
+                ..self
+            },
+        })
+    }
+
+    /// Used for part 2 of the puzzle.
+    pub fn command_2(self, command: &Command) -> Result<Meowmarine, AdventError> {
+        Ok(match command {
+            Command::Forward(amt) => Meowmarine {
+                horizontal: self
+                    .horizontal
+                    .checked_add(*amt)
+                    .ok_or_else(position_overflow)?,
+                depth: self
+                    .aim
+                    .checked_mul(*amt)
+                    .and_then(|dive| self.depth.checked_add(dive))
+                    .ok_or_else(position_overflow)?,
+                ..self
+            },
+            // Note that Down means aim increases
+            Command::Down(amt) => Meowmarine {
+                aim: self.aim.checked_add(*amt).ok_or_else(position_overflow)?,
+                ..self
+            },
+            Command::Up(amt) => Meowmarine {
+                aim: self.aim.checked_sub(*amt).ok_or_else(position_overflow)?,
+                ..self
+            },
+        })
+    }
+
+    /// Value used for the solution.
+    pub fn multiplied(&self) -> Result<isize, AdventError> {
+        self.depth
+            .checked_mul(self.horizontal)
+            .ok_or_else(position_overflow)
+    }
+}
+
+impl Default for Meowmarine {
+    fn default() -> Meowmarine {
+        Meowmarine::new()
+    }
+}
+
+/// Parses one command per line, e.g. "forward 5".
+pub fn parse_commands(lines: &[String]) -> Result<Vec<Command>, AdventError> {
+    let mut commands: Vec<Command> = Vec::new();
+    for value in lines {
+        let split_value: Vec<&str> = value.split(" ").collect();
+        if split_value.len() != 2 {
+            return Err(AdventError::InvalidInput(format!(
+                "Commands must be of the form 'CommandName Value', e.g. 'forward 2'. Got {:?}",
+                value
+            )));
+        }
+
+        let command_name = split_value[0];
+        let command_value = split_value[1].parse::<isize>().map_err(|_| {
+            AdventError::InvalidInput(format!("Value must be an integer. Got {:?}", value))
+        })?;
+
+        match command_name {
+            "forward" => commands.push(Command::Forward(command_value)),
+            "down" => commands.push(Command::Down(command_value)),
+            "up" => commands.push(Command::Up(command_value)),
+            _ => {
+                return Err(AdventError::InvalidInput(format!(
+                    "Invalid command {:?}. Valid commands are: 'up', 'down', 'forward'",
+                    command_name
+                )))
+            }
+        };
+    }
+
+    Ok(commands)
+}
+
+/// Runs every command through `Meowmarine::command_1` (part 1).
+pub fn follow_commands(commands: &[Command]) -> Result<Meowmarine, AdventError> {
+    let mut meowmarine = Meowmarine::new();
+    for command in commands {
+        meowmarine = meowmarine.command_1(command)?;
+        explain!(
+            "{:?}: horizontal {}, depth {}",
+            command,
+            meowmarine.horizontal,
+            meowmarine.depth
+        );
+    }
+
+    Ok(meowmarine)
+}
+
+/// Runs every command through `Meowmarine::command_2`, which steers using aim (part 2).
+pub fn follow_commands_with_aim(commands: &[Command]) -> Result<Meowmarine, AdventError> {
+    let mut meowmarine = Meowmarine::new();
+    for command in commands {
+        meowmarine = meowmarine.command_2(command)?;
+        explain!(
+            "{:?}: horizontal {}, depth {}, aim {}",
+            command,
+            meowmarine.horizontal,
+            meowmarine.depth,
+            meowmarine.aim
+        );
+    }
+
+    Ok(meowmarine)
+}
+
+fn position_overflow() -> AdventError {
+    AdventError::NoSolution("The submarine's position doesn't fit in an isize".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{follow_commands, follow_commands_with_aim, parse_commands, Command};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sample() {
+        let commands = parse_commands(&lines(&[
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            follow_commands(&commands).unwrap().multiplied().unwrap(),
+            150
+        );
+        assert_eq!(
+            follow_commands_with_aim(&commands)
+                .unwrap()
+                .multiplied()
+                .unwrap(),
+            900
+        );
+    }
+
+    #[test]
+    fn test_invalid_commands_are_errors() {
+        assert!(parse_commands(&lines(&["sideways 5"])).is_err());
+        assert!(parse_commands(&lines(&["forward"])).is_err());
+        assert!(parse_commands(&lines(&["forward x"])).is_err());
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        // Fits while following the commands, but not once multiplied.
+        let commands =
+            parse_commands(&lines(&["forward 99999999999999999", "down 99999999999"])).unwrap();
+        assert!(follow_commands(&commands).unwrap().multiplied().is_err());
+
+        // Part 2 multiplies the aim by every forward move.
+        let commands =
+            parse_commands(&lines(&["down 99999999999", "forward 99999999999999999"])).unwrap();
+        assert!(follow_commands_with_aim(&commands).is_err());
+    }
+
+    #[test]
+    fn test_explains_each_command() {
+        let commands = parse_commands(&lines(&["forward 5", "down 2", "forward 3"])).unwrap();
+
+        let (_, explanations) = capture_explanations(|| follow_commands(&commands));
+        assert_eq!(
+            explanations,
+            vec![
+                "Forward(5): horizontal 5, depth 0",
+                "Down(2): horizontal 5, depth 2",
+                "Forward(3): horizontal 8, depth 2",
+            ]
+        );
+
+        let (_, explanations) = capture_explanations(|| follow_commands_with_aim(&commands));
+        assert_eq!(
+            explanations,
+            vec![
+                "Forward(5): horizontal 5, depth 0, aim 0",
+                "Down(2): horizontal 5, depth 0, aim 2",
+                "Forward(3): horizontal 8, depth 6, aim 2",
+            ]
+        );
+    }
+
+    // Reference for both parts, from the totals instead of stepping the submarine.
+    // Part 1's depth is every down minus every up. In part 2, each down (or up)
+    // changes the depth of every forward move after it, so it adds (or takes away)
+    // its amount times the distance still to travel.
+    fn reference(commands: &[Command]) -> (isize, isize) {
+        let mut horizontal = 0;
+        let mut depth = 0;
+        let mut depth_with_aim = 0;
+        for (index, command) in commands.iter().enumerate() {
+            let forward_after: isize = commands[index + 1..]
+                .iter()
+                .map(|command| match command {
+                    Command::Forward(amt) => *amt,
+                    _ => 0,
+                })
+                .sum();
+
+            match command {
+                Command::Forward(amt) => horizontal += amt,
+                Command::Down(amt) => {
+                    depth += amt;
+                    depth_with_aim += amt * forward_after;
+                }
+                Command::Up(amt) => {
+                    depth -= amt;
+                    depth_with_aim -= amt * forward_after;
+                }
+            }
+        }
+
+        (horizontal * depth, horizontal * depth_with_aim)
+    }
+
+    fn command_strategy() -> impl Strategy<Value = Command> {
+        prop_oneof![
+            (0..10isize).prop_map(Command::Forward),
+            (0..10isize).prop_map(Command::Down),
+            (0..10isize).prop_map(Command::Up),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_commands_match_reference(commands in prop::collection::vec(command_strategy(), 0..30)) {
+            let part_1 = follow_commands(&commands).unwrap().multiplied().unwrap();
+            let part_2 = follow_commands_with_aim(&commands).unwrap().multiplied().unwrap();
+            prop_assert_eq!((part_1, part_2), reference(&commands));
+        }
+    }
+}
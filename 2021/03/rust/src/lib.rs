@@ -0,0 +1,321 @@
+//! 2021 day 3: Binary Diagnostic.
+
+use advent_input_parser::{explain, AdventError};
+use std::convert::TryInto;
+
+/// The diagnostic report: a list of binary numbers that all have the same length.
+pub struct DiagnosticReport {
+    /// The numbers in the report, in input order.
+    pub numbers: Vec<usize>,
+    /// How many bits each number has in the input, including leading zeros.
+    pub binary_length: usize,
+}
+
+/// The part 1 rates, built from the most and least common bit in each position.
+pub struct GammaEpsilon {
+    /// Has the most common bit in each position.
+    pub gamma_rate: usize,
+    /// Has the least common bit in each position.
+    pub epsilon_rate: usize,
+}
+
+impl GammaEpsilon {
+    /// The part 1 answer.
+    pub fn power_consumption(&self) -> Result<usize, AdventError> {
+        self.gamma_rate
+            .checked_mul(self.epsilon_rate)
+            .ok_or_else(answer_overflow)
+    }
+}
+
+/// The part 2 ratings, each found by filtering the report down to a single number.
+pub struct OxyCORating {
+    /// The number left after keeping the most common bit at each position.
+    pub oxygen_rating: usize,
+    /// The number left after keeping the least common bit at each position.
+    pub co2_rating: usize,
+}
+
+impl OxyCORating {
+    /// The part 2 answer.
+    pub fn life_support_rating(&self) -> Result<usize, AdventError> {
+        self.oxygen_rating
+            .checked_mul(self.co2_rating)
+            .ok_or_else(answer_overflow)
+    }
+}
+
+fn answer_overflow() -> AdventError {
+    AdventError::NoSolution("The answer doesn't fit in a usize".into())
+}
+
+/// Parses one binary number per line.
+pub fn parse_report(lines: &[String]) -> Result<DiagnosticReport, AdventError> {
+    let mut numbers: Vec<usize> = Vec::new();
+    // Input is all the same length in binary.
+    let mut binary_length = 0;
+    for value in lines {
+        if binary_length == 0 {
+            binary_length = value.len();
+        }
+        if value.len() != binary_length {
+            return Err(AdventError::InvalidInput(format!(
+                "Expected every number to be {} bits long, got {:?}",
+                binary_length, value
+            )));
+        }
+        if binary_length > usize::BITS as usize {
+            return Err(AdventError::InvalidInput(format!(
+                "Numbers can be at most {} bits long, got {:?}",
+                usize::BITS,
+                value
+            )));
+        }
+        let integer_value = usize::from_str_radix(value, 2).map_err(|_| {
+            AdventError::InvalidInput(format!("Expected a binary number, got {:?}", value))
+        })?;
+        numbers.push(integer_value);
+    }
+
+    if numbers.is_empty() {
+        return Err(AdventError::EmptyInput);
+    }
+
+    Ok(DiagnosticReport {
+        numbers,
+        binary_length,
+    })
+}
+
+/// Filters the numbers down bit by bit, from the leftmost bit, keeping the most common bit
+/// for the oxygen rating and the least common for the CO2 rating.
+pub fn oxygen_and_co2_rating(
+    numbers: &[usize],
+    binary_length: usize,
+) -> Result<OxyCORating, AdventError> {
+    let mut valid_oxygen_numbers = numbers.to_vec();
+    let mut valid_co2_numbers = numbers.to_vec();
+    let exp_base: usize = 2;
+
+    // Start by looking at the leftmost bit.
+    let mut offset = binary_length;
+
+    // Once the last bit has been checked, anything left over is a duplicate.
+    while offset > 0 && (valid_oxygen_numbers.len() > 1 || valid_co2_numbers.len() > 1) {
+        offset -= 1;
+
+        let mask = exp_base.pow(offset.try_into().unwrap());
+
+        let position = binary_length - offset;
+
+        if valid_oxygen_numbers.len() > 1 {
+            let gamma_rate = gamma_epsilon_rate(&valid_oxygen_numbers, binary_length).gamma_rate;
+            let gamma_rate_at_offset = (gamma_rate & mask) >> offset;
+
+            let keep = |number: &usize| (number & mask) >> offset == gamma_rate_at_offset;
+            explain!(
+                "Oxygen, bit {}: {} is the most common, eliminating {}",
+                position,
+                gamma_rate_at_offset,
+                eliminated(&valid_oxygen_numbers, keep, binary_length)
+            );
+            valid_oxygen_numbers.retain(keep);
+        }
+
+        if valid_co2_numbers.len() > 1 {
+            let epsilon_rate = gamma_epsilon_rate(&valid_co2_numbers, binary_length).epsilon_rate;
+            let epsilon_rate_at_offset = (epsilon_rate & mask) >> offset;
+
+            let keep = |number: &usize| (number & mask) >> offset == epsilon_rate_at_offset;
+            explain!(
+                "CO2, bit {}: {} is the least common, eliminating {}",
+                position,
+                epsilon_rate_at_offset,
+                eliminated(&valid_co2_numbers, keep, binary_length)
+            );
+            valid_co2_numbers.retain(keep);
+        }
+    }
+
+    Ok(OxyCORating {
+        oxygen_rating: valid_oxygen_numbers.pop().ok_or_else(|| {
+            AdventError::NoSolution("Every number was filtered out of the oxygen rating".into())
+        })?,
+        co2_rating: valid_co2_numbers.pop().ok_or_else(|| {
+            AdventError::NoSolution("Every number was filtered out of the CO2 rating".into())
+        })?,
+    })
+}
+
+// Lists the numbers that keep would filter out, the way they appear in the
+// input, e.g. "00100, 11110". Only called when explaining.
+fn eliminated<F>(numbers: &[usize], keep: F, binary_length: usize) -> String
+where
+    F: Fn(&usize) -> bool,
+{
+    let eliminated: Vec<String> = numbers
+        .iter()
+        .filter(|number| !keep(number))
+        .map(|number| format!("{:0width$b}", number, width = binary_length))
+        .collect();
+
+    if eliminated.is_empty() {
+        return "nothing".into();
+    }
+    eliminated.join(", ")
+}
+
+/// Gamma has the most common bit in each position, epsilon the least common.
+pub fn gamma_epsilon_rate(numbers: &[usize], binary_length: usize) -> GammaEpsilon {
+    let exp_base: usize = 2;
+
+    let mut gamma_rate = 0;
+    let mut epsilon_rate = 0;
+    for x in 0..binary_length {
+        let offset = binary_length - 1 - x;
+        let mask = exp_base.pow(offset.try_into().unwrap());
+
+        let mut leaning_gamma = 0;
+        let mut leaning_epsilon = 0;
+        for number in numbers {
+            let bit = (number & mask) >> offset;
+
+            if bit == 1 {
+                leaning_gamma += 1
+            } else {
+                leaning_epsilon += 1
+            }
+        }
+
+        if leaning_gamma >= leaning_epsilon {
+            gamma_rate ^= mask;
+        } else {
+            epsilon_rate ^= mask;
+        }
+    }
+
+    GammaEpsilon {
+        gamma_rate,
+        epsilon_rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gamma_epsilon_rate, oxygen_and_co2_rating, parse_report};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_explains_eliminated_candidates() {
+        let numbers = vec![0b101, 0b100, 0b011];
+        let (ratings, explanations) = capture_explanations(|| oxygen_and_co2_rating(&numbers, 3));
+        let ratings = ratings.unwrap();
+
+        assert_eq!((ratings.oxygen_rating, ratings.co2_rating), (0b101, 0b011));
+        assert_eq!(
+            explanations,
+            vec![
+                "Oxygen, bit 1: 1 is the most common, eliminating 011",
+                "CO2, bit 1: 0 is the least common, eliminating 101, 100",
+                "Oxygen, bit 2: 0 is the most common, eliminating nothing",
+                "Oxygen, bit 3: 1 is the most common, eliminating 100",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_numbers() {
+        // Filtering can't narrow duplicates down to one number, the last bit ends it instead.
+        let ratings = oxygen_and_co2_rating(&[0b10, 0b10, 0b01], 2).unwrap();
+        assert_eq!((ratings.oxygen_rating, ratings.co2_rating), (0b10, 0b01));
+    }
+
+    #[test]
+    fn test_invalid_reports_are_errors() {
+        assert!(parse_report(&[]).is_err());
+        assert!(parse_report(&["0102".to_string()]).is_err());
+        assert!(parse_report(&["0101".to_string(), "010".to_string()]).is_err());
+        // Too long to fit in a usize.
+        assert!(parse_report(&["0".repeat(70)]).is_err());
+        assert!(parse_report(&["1".repeat(64)]).is_ok());
+    }
+
+    #[test]
+    fn test_answer_overflow_is_an_error() {
+        let high = format!("{}{}", "1".repeat(32), "0".repeat(32));
+        let low = format!("{}{}", "0".repeat(32), "1".repeat(32));
+        let report = parse_report(&[high.clone(), high, low]).unwrap();
+
+        let ge_rates = gamma_epsilon_rate(&report.numbers, report.binary_length);
+        assert!(ge_rates.power_consumption().is_err());
+    }
+
+    // Brute force reference for a rating: a number is the rating if, at every bit,
+    // it agrees with the bit criteria applied to all numbers sharing its prefix so far.
+    // Returns None when no number qualifies (the filtering would run out of numbers).
+    fn brute_force_rating(
+        numbers: &[usize],
+        binary_length: usize,
+        most_common: bool,
+    ) -> Option<usize> {
+        numbers.iter().copied().find(|&candidate| {
+            (0..binary_length).all(|position| {
+                let offset = binary_length - 1 - position;
+                let same_prefix: Vec<usize> = numbers
+                    .iter()
+                    .copied()
+                    .filter(|number| shares_prefix(*number, candidate, binary_length, position))
+                    .collect();
+
+                if same_prefix.len() == 1 {
+                    // Only the candidate is left, filtering has already stopped.
+                    return true;
+                }
+
+                let ones = same_prefix
+                    .iter()
+                    .filter(|number| (*number >> offset) & 1 == 1)
+                    .count();
+                let zeros = same_prefix.len() - ones;
+                // Ties keep 1 for oxygen, and 0 for CO2.
+                let wanted_bit = if most_common {
+                    (ones >= zeros) as usize
+                } else {
+                    (ones < zeros) as usize
+                };
+
+                (candidate >> offset) & 1 == wanted_bit
+            })
+        })
+    }
+
+    fn shares_prefix(a: usize, b: usize, binary_length: usize, prefix_len: usize) -> bool {
+        let shift = binary_length - prefix_len;
+        prefix_len == 0 || (a >> shift) == (b >> shift)
+    }
+
+    // Numbers can repeat, since the report can have the same number more than once.
+    fn input_strategy() -> impl Strategy<Value = (Vec<usize>, usize)> {
+        (1..=8usize).prop_flat_map(|binary_length| {
+            (
+                prop::collection::vec(0..(1usize << binary_length), 1..=(1usize << binary_length)),
+                Just(binary_length),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_ratings_match_brute_force((numbers, binary_length) in input_strategy()) {
+            let oxygen = brute_force_rating(&numbers, binary_length, true);
+            let co2 = brute_force_rating(&numbers, binary_length, false);
+
+            // Filtering down to nothing should be an error rather than a panic.
+            let ratings = oxygen_and_co2_rating(&numbers, binary_length)
+                .ok()
+                .map(|ratings| (ratings.oxygen_rating, ratings.co2_rating));
+            prop_assert_eq!(ratings, oxygen.zip(co2));
+        }
+    }
+}
@@ -0,0 +1,487 @@
+//! 2021 day 4: Giant Squid.
+
+use advent_input_parser::{explain, time_span, AdventError};
+use log::debug;
+use std::fmt;
+
+// The bingo board is a 5x5 array of BingoSquares
+// Represented as a Vec because "[[BingoSquare; 5]; 5]" is hard to
+// create dynamically (for a newb like me at least).
+
+/// A 5x5 bingo board, stored row by row.
+#[derive(Debug)]
+pub struct BingoBoard {
+    // The 25 squares, row by row. bingo_check relies on there being exactly 25,
+    // so this is only set by BingoBoard::new.
+    board: Vec<BingoSquare>,
+    /// Set once the board has had a bingo (used by part 2).
+    pub winner: bool,
+}
+
+/// Width and height of a bingo board.
+pub static SQUARE_SIZE: usize = 5;
+
+impl BingoBoard {
+    /// Given an array with 25 values, we fill the board
+    pub fn new(values: &[usize]) -> Result<BingoBoard, AdventError> {
+        if values.len() != SQUARE_SIZE * SQUARE_SIZE {
+            return Err(AdventError::InvalidInput(format!(
+                "Bingo boards must have {} values, found one with {}",
+                SQUARE_SIZE * SQUARE_SIZE,
+                values.len()
+            )));
+        }
+
+        let mut board: Vec<BingoSquare> = Vec::new();
+        for value in values {
+            board.push(BingoSquare {
+                value: *value,
+                marked: false,
+            });
+        }
+
+        Ok(BingoBoard {
+            board,
+            winner: false,
+        })
+    }
+
+    /// The 25 squares, row by row.
+    pub fn squares(&self) -> &[BingoSquare] {
+        &self.board
+    }
+
+    // Given a row and col, return the offset into the vec that corresponds.
+    fn vec_offset(row: usize, col: usize) -> usize {
+        row * SQUARE_SIZE + col
+    }
+
+    /// Attempt to mark a value as a match, and return True if a match was found
+    /// for this board.
+    pub fn mark_match(&mut self, value: usize) -> bool {
+        for square in self.board.iter_mut() {
+            if square.value == value {
+                square.mark();
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns true if any full row or column is marked.
+    pub fn bingo_check(&self) -> bool {
+        // Check all rows for a bingo
+        let mut result = false;
+
+        for row in 0..SQUARE_SIZE {
+            let start_offset = BingoBoard::vec_offset(row, 0);
+            let end_offset = start_offset + SQUARE_SIZE;
+
+            result = true;
+            for value in start_offset..end_offset {
+                if !self.board.get(value).unwrap().marked {
+                    result = false;
+                    break;
+                }
+            }
+
+            if result {
+                return result;
+            }
+        }
+
+        // Next, check the columns
+        for col in 0..SQUARE_SIZE {
+            result = true;
+            // With a 5x5 board, this will yield the values 0, 5, 10, 15, 20
+            for value in (0..SQUARE_SIZE * SQUARE_SIZE).step_by(SQUARE_SIZE) {
+                if !self.board.get(value + col).unwrap().marked {
+                    result = false;
+                    break;
+                }
+            }
+
+            if result {
+                return result;
+            }
+        }
+
+        result
+    }
+
+    /// Board score is the sum of all unmarked squares, multiplied by the last number marked.
+    pub fn board_score(&self, last_number: usize) -> Result<usize, AdventError> {
+        let score_overflow =
+            || AdventError::NoSolution("The board's score doesn't fit in a usize".into());
+
+        let board_sum = self
+            .board
+            .iter()
+            .filter_map(|square| {
+                if !square.marked {
+                    Some(square.value)
+                } else {
+                    None
+                }
+            })
+            .try_fold(0usize, |sum, value| sum.checked_add(value))
+            .ok_or_else(score_overflow)?;
+
+        debug!(
+            "Found a bingo - number: {:?}, sum: {:?}",
+            last_number, board_sum
+        );
+
+        board_sum
+            .checked_mul(last_number)
+            .ok_or_else(score_overflow)
+    }
+}
+
+// Shows the board as a grid, with marked numbers in brackets.
+impl fmt::Display for BingoBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.board.chunks(SQUARE_SIZE) {
+            let squares: Vec<String> = row
+                .iter()
+                .map(|square| {
+                    if square.marked {
+                        format!("[{:>2}]", square.value)
+                    } else {
+                        format!(" {:>2} ", square.value)
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", squares.join(""))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A single number on a bingo board.
+#[derive(Debug)]
+pub struct BingoSquare {
+    /// The number on the square.
+    pub value: usize,
+    /// Whether the number has been drawn.
+    pub marked: bool,
+}
+
+impl BingoSquare {
+    /// Marks the square as drawn.
+    pub fn mark(&mut self) {
+        self.marked = true;
+    }
+}
+
+/// The numbers to draw, in order, and the boards to play them on.
+pub struct BingoGame {
+    /// The numbers to draw, in order.
+    pub numbers: Vec<usize>,
+    /// Every board in the game, in input order.
+    pub boards: Vec<BingoBoard>,
+}
+
+/// Parses the comma separated numbers on the first line, followed by the boards.
+pub fn parse_game(lines: &[String]) -> Result<BingoGame, AdventError> {
+    let (first_line, lines) = lines.split_first().ok_or(AdventError::EmptyInput)?;
+
+    let bingo_numbers = parse_numbers(first_line.split(","))?;
+
+    let boards_span = time_span("boards");
+    let mut all_boards: Vec<BingoBoard> = Vec::new();
+    let mut current_board: Vec<usize> = Vec::new();
+    for line in lines {
+        if line.is_empty() {
+            // There's an extra newline before the first bingo board starts - skip it.
+            if !current_board.is_empty() {
+                all_boards.push(BingoBoard::new(&current_board)?);
+                current_board = Vec::new();
+            }
+        } else {
+            let mut new_bingo_line = parse_numbers(line.split_whitespace())?;
+            if new_bingo_line.len() != SQUARE_SIZE {
+                return Err(AdventError::InvalidInput(format!(
+                    "Bingo board rows must have {} values, got {:?}",
+                    SQUARE_SIZE, line
+                )));
+            }
+            current_board.append(&mut new_bingo_line);
+        }
+    }
+
+    if !current_board.is_empty() {
+        // Input didnt end with an empty line, final board to add
+        all_boards.push(BingoBoard::new(&current_board)?);
+    }
+
+    if all_boards.is_empty() {
+        return Err(AdventError::InvalidInput("No bingo boards found".into()));
+    }
+    drop(boards_span);
+
+    Ok(BingoGame {
+        numbers: bingo_numbers,
+        boards: all_boards,
+    })
+}
+
+fn parse_numbers<'a, I>(values: I) -> Result<Vec<usize>, AdventError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    values
+        .map(|v| {
+            v.parse::<usize>()
+                .map_err(|_| AdventError::InvalidInput(format!("Expected an integer, got {:?}", v)))
+        })
+        .collect()
+}
+
+/// Return the first winner board score
+pub fn bingo_bango(
+    bingo_numbers: &[usize],
+    all_boards: &mut [BingoBoard],
+) -> Result<usize, AdventError> {
+    for bingo_number in bingo_numbers {
+        for (index, board) in all_boards.iter_mut().enumerate() {
+            let match_found = board.mark_match(*bingo_number);
+            if match_found && board.bingo_check() {
+                explain!(
+                    "Board {} is the first to win, after {} is drawn:\n{}",
+                    index + 1,
+                    bingo_number,
+                    board
+                );
+                return board.board_score(*bingo_number);
+            }
+        }
+    }
+
+    Err(AdventError::NoSolution("No board got a bingo".into()))
+}
+
+/// Return the score of the board that will win last
+pub fn bingo_loseo(
+    bingo_numbers: &[usize],
+    all_boards: &mut [BingoBoard],
+) -> Result<usize, AdventError> {
+    let mut non_winning_boards = all_boards.len();
+
+    for bingo_number in bingo_numbers {
+        for (index, board) in all_boards.iter_mut().enumerate() {
+            if board.winner {
+                continue;
+            }
+
+            let match_found = board.mark_match(*bingo_number);
+            if match_found && board.bingo_check() {
+                board.winner = true;
+                non_winning_boards -= 1;
+                explain!(
+                    "Board {} wins after {} is drawn, {} boards left:\n{}",
+                    index + 1,
+                    bingo_number,
+                    non_winning_boards,
+                    board
+                );
+            }
+
+            if non_winning_boards == 0 {
+                // This was the last board to win - now we can report the score
+                return board.board_score(*bingo_number);
+            }
+        }
+    }
+
+    Err(AdventError::NoSolution(
+        "Not every board got a bingo".into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bingo_bango, bingo_loseo, parse_game, BingoBoard, BingoGame, SQUARE_SIZE};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    // Each board is the given first row, followed by the rows 10-14 up to 25-29.
+    fn game(numbers: &str, first_rows: &[&str]) -> BingoGame {
+        let mut input = vec![numbers.to_string()];
+        for first_row in first_rows {
+            input.push(String::new());
+            input.push(first_row.to_string());
+            for row in 0..4 {
+                let start = 10 + row * 5;
+                let values: Vec<String> = (start..start + 5).map(|v| v.to_string()).collect();
+                input.push(values.join(" "));
+            }
+        }
+
+        parse_game(&input).unwrap()
+    }
+
+    #[test]
+    fn test_first_and_last_winner() {
+        let rows_10_to_29: usize = (10..30).sum();
+        let mut game = game("1,2,3,4,5,6,7,8,9", &["1 2 3 4 5", "5 6 7 8 9"]);
+
+        assert_eq!(
+            bingo_bango(&game.numbers, &mut game.boards).unwrap(),
+            rows_10_to_29 * 5
+        );
+        assert_eq!(
+            bingo_loseo(&game.numbers, &mut game.boards).unwrap(),
+            rows_10_to_29 * 9
+        );
+    }
+
+    #[test]
+    fn test_explains_winning_boards() {
+        let mut first = game("1,2,3,4,5,6,7,8,9", &["1 2 3 4 5", "5 6 7 8 9"]);
+        let (_, explanations) =
+            capture_explanations(|| bingo_bango(&first.numbers, &mut first.boards));
+        assert_eq!(explanations.len(), 1);
+        assert!(explanations[0].starts_with("Board 1 is the first to win, after 5 is drawn:\n"));
+        assert!(explanations[0].contains("[ 1][ 2][ 3][ 4][ 5]"));
+
+        let mut last = game("1,2,3,4,5,6,7,8,9", &["1 2 3 4 5", "5 6 7 8 9"]);
+        let (_, explanations) =
+            capture_explanations(|| bingo_loseo(&last.numbers, &mut last.boards));
+        let headers: Vec<&str> = explanations
+            .iter()
+            .map(|explanation| explanation.lines().next().unwrap())
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                "Board 1 wins after 5 is drawn, 1 boards left:",
+                "Board 2 wins after 9 is drawn, 0 boards left:",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_winner_is_an_error() {
+        let mut game = game("1,2,3", &["1 2 3 4 5"]);
+
+        assert!(bingo_bango(&game.numbers, &mut game.boards).is_err());
+        assert!(bingo_loseo(&game.numbers, &mut game.boards).is_err());
+    }
+
+    #[test]
+    fn test_invalid_games_are_errors() {
+        let lines =
+            |values: &[&str]| -> Vec<String> { values.iter().map(|v| v.to_string()).collect() };
+
+        assert!(parse_game(&[]).is_err());
+        // No boards after the numbers.
+        assert!(parse_game(&lines(&["1,2,3"])).is_err());
+        // A board that's missing rows.
+        assert!(parse_game(&lines(&["1,2,3", "", "1 2 3 4 5"])).is_err());
+        assert!(parse_game(&lines(&["1,x,3", "", "1 2 3 4 5"])).is_err());
+        // 25 values, but not 5 to a row.
+        assert!(parse_game(&lines(&[
+            "1,2,3",
+            "",
+            "1 2 3 4 5 6",
+            "7 8 9 10",
+            "11 12 13 14 15",
+            "16 17 18 19 20",
+            "21 22 23 24 25",
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_score_overflow_is_an_error() {
+        let mut game = game("1,2,3,4,5", &["1 2 3 4 5"]);
+        game.boards[0].board[5].value = usize::MAX;
+
+        assert!(bingo_bango(&game.numbers, &mut game.boards).is_err());
+    }
+
+    // Reference: instead of marking boards as numbers are drawn, work out the turn each
+    // board wins on up front. A row or column is complete on the turn its last number is
+    // drawn, and a board wins with its earliest complete row or column.
+    fn winning_turn(board: &[usize], numbers: &[usize]) -> Option<usize> {
+        let turn = |value: &usize| numbers.iter().position(|number| number == value);
+        let rows = (0..SQUARE_SIZE).map(|row| {
+            (0..SQUARE_SIZE)
+                .map(|col| turn(&board[row * SQUARE_SIZE + col]))
+                .collect::<Option<Vec<usize>>>()
+        });
+        let cols = (0..SQUARE_SIZE).map(|col| {
+            (0..SQUARE_SIZE)
+                .map(|row| turn(&board[row * SQUARE_SIZE + col]))
+                .collect::<Option<Vec<usize>>>()
+        });
+
+        rows.chain(cols)
+            .filter_map(|turns| turns?.into_iter().max())
+            .min()
+    }
+
+    // The score of a board that wins on the given turn.
+    fn score_on_turn(board: &[usize], numbers: &[usize], turn: usize) -> usize {
+        let unmarked: usize = board
+            .iter()
+            .filter(|value| !numbers[..=turn].contains(value))
+            .sum();
+        unmarked * numbers[turn]
+    }
+
+    // Returns the first and last winners' scores. Boards that win on the same turn
+    // are checked in input order, so the first winner is the earliest of them and the
+    // last winner the latest.
+    fn reference(numbers: &[usize], boards: &[Vec<usize>]) -> (Option<usize>, Option<usize>) {
+        let turns: Vec<Option<usize>> = boards
+            .iter()
+            .map(|board| winning_turn(board, numbers))
+            .collect();
+
+        let first = turns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, turn)| Some((index, (*turn)?)))
+            .min_by_key(|(_, turn)| *turn)
+            .map(|(index, turn)| score_on_turn(&boards[index], numbers, turn));
+
+        let last = turns
+            .iter()
+            .copied()
+            .collect::<Option<Vec<usize>>>()
+            .and_then(|turns| turns.into_iter().enumerate().max_by_key(|(_, turn)| *turn))
+            .map(|(index, turn)| score_on_turn(&boards[index], numbers, turn));
+
+        (first, last)
+    }
+
+    // Like the real input, the drawn numbers and the numbers on each board don't repeat.
+    fn game_strategy() -> impl Strategy<Value = (Vec<usize>, Vec<Vec<usize>>)> {
+        let all_numbers: Vec<usize> = (0..50).collect();
+        let numbers = (Just(all_numbers.clone()).prop_shuffle(), 5..=50usize)
+            .prop_map(|(numbers, drawn)| numbers[..drawn].to_vec());
+        let board = Just(all_numbers)
+            .prop_shuffle()
+            .prop_map(|values| values[..SQUARE_SIZE * SQUARE_SIZE].to_vec());
+
+        (numbers, prop::collection::vec(board, 1..5))
+    }
+
+    proptest! {
+        #[test]
+        fn test_winners_match_reference((numbers, boards) in game_strategy()) {
+            let mut first_boards: Vec<BingoBoard> =
+                boards.iter().map(|board| BingoBoard::new(board).unwrap()).collect();
+            let mut last_boards: Vec<BingoBoard> =
+                boards.iter().map(|board| BingoBoard::new(board).unwrap()).collect();
+
+            let first = bingo_bango(&numbers, &mut first_boards).ok();
+            let last = bingo_loseo(&numbers, &mut last_boards).ok();
+            prop_assert_eq!((first, last), reference(&numbers, &boards));
+        }
+    }
+}
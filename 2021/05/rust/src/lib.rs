@@ -0,0 +1,439 @@
+//! 2021 day 5: Hydrothermal Venture.
+
+use advent_input_parser::{explain, AdventError};
+use log::debug;
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A point on the ocean floor.
+pub struct Point {
+    /// Distance from the left edge of the map.
+    pub x: usize,
+    /// Distance from the top edge of the map.
+    pub y: usize,
+}
+
+impl Point {
+    /// Parse a point from its x and y components.
+    pub fn new(x: Option<&str>, y: Option<&str>) -> Result<Point, AdventError> {
+        Ok(Point {
+            x: Point::from_option_str(x)?,
+            y: Point::from_option_str(y)?,
+        })
+    }
+
+    fn from_option_str(value: Option<&str>) -> Result<usize, AdventError> {
+        let value = value
+            .ok_or_else(|| AdventError::InvalidInput("Points must be of the form 'x,y'".into()))?;
+
+        value.parse::<usize>().map_err(|_| {
+            AdventError::InvalidInput(format!("Point must be an integer, got {:?}", value))
+        })
+    }
+}
+
+// The points are private so that every Line goes through Line::new's check
+// that it's horizontal, vertical or 45 degrees.
+/// A line of hydrothermal vents, from start to end inclusive.
+pub struct Line {
+    start: Point,
+    end: Point,
+}
+
+impl Line {
+    /// Parse a line from its "x,y" endpoints. Only horizontal, vertical and
+    /// 45 degree lines are accepted.
+    pub fn new(start_coord: Option<&str>, end_coord: Option<&str>) -> Result<Line, AdventError> {
+        let (start_coord, end_coord) = match (start_coord, end_coord) {
+            (Some(start_coord), Some(end_coord)) => (start_coord, end_coord),
+            _ => {
+                return Err(AdventError::InvalidInput(
+                    "Lines must be of the form 'x1,y1 -> x2,y2'".into(),
+                ))
+            }
+        };
+
+        let mut start_split = start_coord.split(",");
+        let mut end_split = end_coord.split(",");
+        let line = Line {
+            start: Point::new(start_split.next(), start_split.next())?,
+            end: Point::new(end_split.next(), end_split.next())?,
+        };
+
+        // Map::mark_line only knows how to draw straight and 45 degree lines.
+        let x_distance = line.start.x.abs_diff(line.end.x);
+        let y_distance = line.start.y.abs_diff(line.end.y);
+        if x_distance != 0 && y_distance != 0 && x_distance != y_distance {
+            return Err(AdventError::InvalidInput(format!(
+                "Line {} -> {} is not horizontal, vertical or diagonal",
+                start_coord, end_coord
+            )));
+        }
+
+        Ok(line)
+    }
+
+    /// The first point, as written in the input.
+    pub fn start(&self) -> &Point {
+        &self.start
+    }
+
+    /// The last point, as written in the input.
+    pub fn end(&self) -> &Point {
+        &self.end
+    }
+}
+
+impl fmt::Debug for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{} -> {},{}",
+            self.start.x, self.start.y, self.end.x, self.end.y
+        )
+    }
+}
+
+// This started out very similar to our BingoBoard from day 4, as a Vec covering
+// every point. The x/y axis range is only known once the input is read, and a single
+// line out to 1000000 would need a trillion points, so only the points lines
+// actually cross are stored.
+/// The points lines cross on the ocean floor, and how many lines cross each one.
+#[derive(Debug)]
+pub struct Map {
+    coordinates: HashMap<(usize, usize), Coordinate>,
+    size: usize,
+}
+
+impl Map {
+    /// Initialize a new empty map using the known x/y max.
+    pub fn new(size: usize) -> Map {
+        Map {
+            coordinates: HashMap::new(),
+            size,
+        }
+    }
+
+    /// Mark every point on a line. Diagonal lines are skipped unless
+    /// consider_diagonals is set. Errors if the line goes past the map's size.
+    pub fn mark_line(&mut self, line: &Line, consider_diagonals: bool) -> Result<(), AdventError> {
+        let start_point = line.start();
+        let end_point = line.end();
+
+        // Part 1 doesn't care about diagonals
+        if start_point.x != end_point.x && start_point.y != end_point.y && !consider_diagonals {
+            return Ok(());
+        }
+
+        if cmp::max(start_point.x, end_point.x) > self.size
+            || cmp::max(start_point.y, end_point.y) > self.size
+        {
+            return Err(AdventError::InvalidInput(format!(
+                "Line {:?} doesn't fit on a map of size {:?}",
+                line, self.size
+            )));
+        }
+
+        // Straight lines only move along one axis, and 45 degree lines move the
+        // same distance along both, so the longer distance is the number of steps.
+        let steps = cmp::max(
+            start_point.x.abs_diff(end_point.x),
+            start_point.y.abs_diff(end_point.y),
+        );
+        let too_long =
+            || AdventError::InvalidInput(format!("Line {:?} has too many points to mark", line));
+        let points = steps.checked_add(1).ok_or_else(too_long)?;
+        self.coordinates
+            .try_reserve(points)
+            .map_err(|_| too_long())?;
+
+        for step in 0..=steps {
+            let x_value = step_towards(start_point.x, end_point.x, step);
+            let y_value = step_towards(start_point.y, end_point.y, step);
+            self.coordinates
+                .entry((x_value, y_value))
+                .or_default()
+                .mark();
+        }
+
+        Ok(())
+    }
+
+    /// The answer is the number of points where >= 2 points overlap
+    pub fn result(&self) -> usize {
+        self.coordinates
+            .values()
+            .filter(|coord| coord.vent_count >= 2)
+            .fold(0, |acc, _| acc + 1)
+    }
+}
+
+// The value step places from start, in the direction of end.
+fn step_towards(start: usize, end: usize, step: usize) -> usize {
+    match end.cmp(&start) {
+        cmp::Ordering::Greater => start + step,
+        cmp::Ordering::Less => start - step,
+        cmp::Ordering::Equal => start,
+    }
+}
+
+/// A single point on the map.
+#[derive(Debug)]
+pub struct Coordinate {
+    /// How many lines pass through this point.
+    pub vent_count: usize,
+}
+
+impl Coordinate {
+    /// A point that no lines pass through yet.
+    pub fn new() -> Coordinate {
+        Coordinate { vent_count: 0 }
+    }
+
+    /// Counts one more line passing through this point.
+    pub fn mark(&mut self) {
+        self.vent_count += 1
+    }
+}
+
+impl Default for Coordinate {
+    fn default() -> Coordinate {
+        Coordinate::new()
+    }
+}
+
+/// Parse every "x1,y1 -> x2,y2" input line.
+pub fn parse_lines(input: &[String]) -> Result<Vec<Line>, AdventError> {
+    let mut lines: Vec<Line> = Vec::new();
+
+    for input_line in input {
+        let mut split_line = input_line.split(" -> ");
+        lines.push(Line::new(split_line.next(), split_line.next())?);
+    }
+
+    if lines.is_empty() {
+        return Err(AdventError::EmptyInput);
+    }
+
+    Ok(lines)
+}
+
+/// Initialize a map as a square
+/// No downside to empty coordinates if one axis is smaller
+pub fn map_size(lines: &[Line]) -> usize {
+    let x_axis_max = lines.iter().fold(0, |acc, line| {
+        cmp::max(cmp::max(acc, line.start.x), line.end.x)
+    });
+    let y_axis_max = lines.iter().fold(0, |acc, line| {
+        cmp::max(cmp::max(acc, line.start.y), line.end.y)
+    });
+
+    cmp::max(x_axis_max, y_axis_max)
+}
+
+/// Draw every line onto a map and count the points where at least two overlap.
+pub fn overlap_count(lines: &[Line], consider_diagonals: bool) -> Result<usize, AdventError> {
+    let mut map = Map::new(map_size(lines));
+    for line in lines {
+        if !consider_diagonals && line.start.x != line.end.x && line.start.y != line.end.y {
+            explain!("{:?}: diagonal, skipped", line);
+        } else {
+            explain!("{:?}: marked", line);
+        }
+        map.mark_line(line, consider_diagonals)?;
+    }
+
+    debug!("Lines cross {:?} points", map.coordinates.len());
+
+    let result = map.result();
+    explain!(
+        "{} points have at least 2 lines, and at most {} lines cross a single point",
+        result,
+        map.coordinates
+            .values()
+            .map(|coord| coord.vent_count)
+            .max()
+            .unwrap_or(0)
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{overlap_count, parse_lines, Line, Map, Point};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sample() {
+        let lines = parse_lines(&lines(&[
+            "0,9 -> 5,9",
+            "8,0 -> 0,8",
+            "9,4 -> 3,4",
+            "2,2 -> 2,1",
+            "7,0 -> 7,4",
+            "6,4 -> 2,0",
+            "0,9 -> 2,9",
+            "3,4 -> 1,4",
+            "0,0 -> 8,8",
+            "5,5 -> 8,2",
+        ]))
+        .unwrap();
+
+        assert_eq!(overlap_count(&lines, false).unwrap(), 5);
+        assert_eq!(overlap_count(&lines, true).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_invalid_lines_are_errors() {
+        // Neither straight nor 45 degrees.
+        assert!(parse_lines(&lines(&["0,0 -> 2,1"])).is_err());
+        assert!(parse_lines(&[format!("0,0 -> {},1", usize::MAX)]).is_err());
+        assert!(parse_lines(&lines(&["0,0 -> 2"])).is_err());
+        assert!(parse_lines(&lines(&["0,0 2,2"])).is_err());
+        assert!(parse_lines(&lines(&["a,0 -> 2,0"])).is_err());
+        assert!(parse_lines(&[]).is_err());
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        // Only the points on lines are stored, however far out they are.
+        let lines = parse_lines(&lines(&[
+            "4000000000,0 -> 4000000000,5",
+            "4000000000,3 -> 4000000000,9",
+            "0,0 -> 1,1",
+        ]))
+        .unwrap();
+        assert_eq!(overlap_count(&lines, false).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_line_off_the_map_is_an_error() {
+        let line = Line::new(Some("0,0"), Some("5,5")).unwrap();
+        assert!(Map::new(4).mark_line(&line, true).is_err());
+        assert!(Map::new(5).mark_line(&line, true).is_ok());
+        // Part 1 skips diagonals without looking at where they are.
+        assert!(Map::new(4).mark_line(&line, false).is_ok());
+    }
+
+    #[test]
+    fn test_line_too_long_is_an_error() {
+        // Too many points to store, but the coordinates themselves fit in a usize.
+        let lines = parse_lines(&lines(&["0,0 -> 1152921504606846976,0"])).unwrap();
+        assert!(overlap_count(&lines, false).is_err());
+
+        let max = usize::MAX.to_string();
+        let lines = parse_lines(&[format!("0,0 -> {},0", max)]).unwrap();
+        assert!(overlap_count(&lines, false).is_err());
+    }
+
+    #[test]
+    fn test_explains_lines_and_overlaps() {
+        let lines = parse_lines(&lines(&["0,0 -> 2,0", "1,0 -> 1,2", "0,0 -> 2,2"])).unwrap();
+
+        let (_, explanations) = capture_explanations(|| overlap_count(&lines, false));
+        assert_eq!(
+            explanations,
+            vec![
+                "0,0 -> 2,0: marked",
+                "1,0 -> 1,2: marked",
+                "0,0 -> 2,2: diagonal, skipped",
+                "1 points have at least 2 lines, and at most 2 lines cross a single point",
+            ]
+        );
+
+        let (_, explanations) = capture_explanations(|| overlap_count(&lines, true));
+        assert_eq!(
+            explanations.last().unwrap(),
+            "3 points have at least 2 lines, and at most 2 lines cross a single point"
+        );
+    }
+
+    // Reference implementation for the map: instead of rasterizing every line
+    // onto a grid, walk the points of each line and check whether any other
+    // line passes through that point. Slow, but hard to get wrong.
+    fn pairwise_overlaps(lines: &[Line], consider_diagonals: bool) -> usize {
+        let lines: Vec<&Line> = lines
+            .iter()
+            .filter(|line| consider_diagonals || !is_diagonal(line))
+            .collect();
+
+        let mut overlaps: HashSet<(usize, usize)> = HashSet::new();
+        for (i, line) in lines.iter().enumerate() {
+            for (j, other) in lines.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                for point in points_on(line) {
+                    if is_on_line(point, other) {
+                        overlaps.insert(point);
+                    }
+                }
+            }
+        }
+
+        overlaps.len()
+    }
+
+    fn is_diagonal(line: &Line) -> bool {
+        line.start.x != line.end.x && line.start.y != line.end.y
+    }
+
+    fn points_on(line: &Line) -> Vec<(usize, usize)> {
+        let (x1, y1) = (line.start.x as i64, line.start.y as i64);
+        let (x2, y2) = (line.end.x as i64, line.end.y as i64);
+        let steps = (x2 - x1).abs().max((y2 - y1).abs());
+        (0..=steps)
+            .map(|step| {
+                (
+                    (x1 + step * (x2 - x1).signum()) as usize,
+                    (y1 + step * (y2 - y1).signum()) as usize,
+                )
+            })
+            .collect()
+    }
+
+    // A point is on a (horizontal, vertical or 45 degree) line if it's inside
+    // the line's bounding box and collinear with its endpoints.
+    fn is_on_line(point: (usize, usize), line: &Line) -> bool {
+        let (px, py) = (point.0 as i64, point.1 as i64);
+        let (x1, y1) = (line.start.x as i64, line.start.y as i64);
+        let (x2, y2) = (line.end.x as i64, line.end.y as i64);
+
+        let in_box = px >= x1.min(x2) && px <= x1.max(x2) && py >= y1.min(y2) && py <= y1.max(y2);
+        let collinear = (x2 - x1) * (py - y1) == (y2 - y1) * (px - x1);
+        in_box && collinear
+    }
+
+    // Only horizontal, vertical and 45 degree lines are valid input.
+    fn line_strategy() -> impl Strategy<Value = Line> {
+        (0..10usize, 0..10usize, 0..10usize, 0..10usize)
+            .prop_filter("line must be straight or 45 degrees", |(x1, y1, x2, y2)| {
+                x1 == x2
+                    || y1 == y2
+                    || (*x1 as i64 - *x2 as i64).abs() == (*y1 as i64 - *y2 as i64).abs()
+            })
+            .prop_map(|(x1, y1, x2, y2)| Line {
+                start: Point { x: x1, y: y1 },
+                end: Point { x: x2, y: y2 },
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_map_matches_pairwise_without_diagonals(lines in prop::collection::vec(line_strategy(), 1..12)) {
+            prop_assert_eq!(overlap_count(&lines, false).unwrap(), pairwise_overlaps(&lines, false));
+        }
+
+        #[test]
+        fn test_map_matches_pairwise_with_diagonals(lines in prop::collection::vec(line_strategy(), 1..12)) {
+            prop_assert_eq!(overlap_count(&lines, true).unwrap(), pairwise_overlaps(&lines, true));
+        }
+    }
+}
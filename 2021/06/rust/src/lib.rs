@@ -0,0 +1,277 @@
+//! 2021 day 6: Lanternfish.
+
+use advent_input_parser::{explain, AdventError};
+use std::collections::HashMap;
+
+// NOTE: BreedingPool was my naive implementation that got through part 1.
+// BreedingPoolV2 is the vastly more memory efficient version that
+// can actually handle the exponential growth in part 2.
+
+/// The naive pool, which keeps track of every fish individually.
+// this is an MTG reference pls don't think differently
+pub struct BreedingPool {
+    /// Every fish in the pool, one entry each.
+    pub fishies: Vec<Fish>,
+}
+
+impl BreedingPool {
+    /// Convert a vector of sizes into lil fishies.
+    pub fn new(timers: &[u8]) -> BreedingPool {
+        let mut fishies: Vec<Fish> = Vec::new();
+
+        for timer in timers {
+            fishies.push(Fish::new(*timer));
+        }
+
+        BreedingPool { fishies }
+    }
+
+    /// Progress the pool by X days
+    pub fn tick_days(&mut self, days: usize) {
+        for day in 1..days + 1 {
+            let mut new_fish = 0;
+            for fish in self.fishies.iter_mut() {
+                if fish.tick() {
+                    new_fish += 1;
+                }
+            }
+
+            for _ in 0..new_fish {
+                self.fishies.push(Fish::new_baby());
+            }
+
+            explain!("Day {}: {} fish", day, self.total_fish());
+        }
+    }
+
+    /// How many fish are in the pool.
+    pub fn total_fish(&self) -> usize {
+        self.fishies.len()
+    }
+}
+
+/// A single fish and its breeding timer.
+pub struct Fish {
+    /// Days until this fish creates a new one.
+    pub timer: u8,
+}
+
+/// Timer a fish resets to after breeding.
+pub static DEFAULT_TIMER: u8 = 6;
+/// Timer a newly born fish starts with.
+pub static DEFAULT_BABY_TIMER: u8 = DEFAULT_TIMER + 2;
+
+impl Fish {
+    /// Initializes a fish with a specific timer
+    pub fn new(timer: u8) -> Fish {
+        Fish { timer }
+    }
+
+    /// Creates a new baby fish using the static DEFAULT_BABY_TIMER
+    pub fn new_baby() -> Fish {
+        Fish {
+            timer: DEFAULT_BABY_TIMER,
+        }
+    }
+
+    /// Progresses the timer, and returns True if the timer reset
+    /// and a new fishie should be created
+    pub fn tick(&mut self) -> bool {
+        if self.timer == 0 {
+            self.timer = DEFAULT_TIMER;
+            true
+        } else {
+            self.timer -= 1;
+            false
+        }
+    }
+}
+
+/// The pool used for part 2, which only counts how many fish share each timer.
+#[derive(Debug)]
+pub struct BreedingPoolV2 {
+    // How many fish there are for each timer value. tick_days relies on every timer
+    // from 0 to DEFAULT_BABY_TIMER having an entry, so this stays private.
+    fishies: HashMap<u8, usize>,
+}
+
+impl BreedingPoolV2 {
+    /// Counts the fish for each timer. Errors if a timer is larger than DEFAULT_BABY_TIMER.
+    pub fn new(timers: &[u8]) -> Result<BreedingPoolV2, AdventError> {
+        let mut fishies = HashMap::new();
+
+        // Fill in all keys with 0s from 0-DEFAULT_BABY_TIMER (inclusive, hence + 1)
+        for k in 0..DEFAULT_BABY_TIMER + 1 {
+            fishies.insert(k, 0);
+        }
+
+        for timer in timers {
+            let value = fishies.get_mut(timer).ok_or_else(|| {
+                AdventError::InvalidInput(format!(
+                    "Fish timers can't be larger than {:?}, got {:?}",
+                    DEFAULT_BABY_TIMER, timer
+                ))
+            })?;
+            *value += 1;
+        }
+
+        Ok(BreedingPoolV2 { fishies })
+    }
+
+    /// Progress the pool by X days. Errors if the number of fish stops fitting in a usize.
+    pub fn tick_days(&mut self, days: usize) -> Result<(), AdventError> {
+        for day in 1..days + 1 {
+            // Amount of new fish is the number of fish @ 0
+            // This is also the amount to add to the DEFAULT_TIMER
+            // bucket, because they are grown up fishies that need their timer
+            // reset.
+            let new_fish = self.fishies.remove(&0).unwrap();
+
+            // Handle all buckets but 0. 0 is a special case handled at the end,
+            // all other buckets get their number moved down 1.
+            for bucket in 1..DEFAULT_BABY_TIMER + 1 {
+                let new_bucket_amount = self.fishies.remove(&bucket).unwrap();
+                self.fishies.insert(bucket - 1, new_bucket_amount);
+            }
+
+            // This key is now empty, now that we've moved down all fish.
+            self.fishies.insert(DEFAULT_BABY_TIMER, new_fish);
+            // The key at DEFAULT_TIMER is not empty yet, so we add to it
+            self.fishies.insert(
+                DEFAULT_TIMER,
+                self.fishies
+                    .get(&DEFAULT_TIMER)
+                    .unwrap()
+                    .checked_add(new_fish)
+                    .ok_or_else(fish_overflow)?,
+            );
+
+            explain!(
+                "Day {}: {:?} fish by timer, {} fish",
+                day,
+                self.fish_by_timer(),
+                self.total_fish()?
+            );
+        }
+
+        Ok(())
+    }
+
+    /// How many fish are in the pool, across every timer.
+    pub fn total_fish(&self) -> Result<usize, AdventError> {
+        self.fishies
+            .values()
+            .try_fold(0usize, |total, fish| total.checked_add(*fish))
+            .ok_or_else(fish_overflow)
+    }
+
+    /// How many fish have each timer, ordered from timer 0 to DEFAULT_BABY_TIMER.
+    pub fn fish_by_timer(&self) -> Vec<usize> {
+        (0..DEFAULT_BABY_TIMER + 1)
+            .map(|timer| self.fishies[&timer])
+            .collect()
+    }
+}
+
+fn fish_overflow() -> AdventError {
+    AdventError::NoSolution("The number of fish doesn't fit in a usize".into())
+}
+
+/// Parse the single line of comma separated fish timers.
+pub fn parse_timers(input: &[String]) -> Result<Vec<u8>, AdventError> {
+    // This problem only has 1 line of input.
+    let input = input.first().ok_or(AdventError::EmptyInput)?;
+
+    let mut timers: Vec<u8> = Vec::new();
+    for timer in input.split(",") {
+        let timer = timer
+            .parse::<u8>()
+            .ok()
+            .filter(|timer| *timer <= DEFAULT_BABY_TIMER)
+            .ok_or_else(|| {
+                AdventError::InvalidInput(format!(
+                    "Fish timers must be integers from 0 to {:?}, got {:?}",
+                    DEFAULT_BABY_TIMER, timer
+                ))
+            })?;
+        timers.push(timer);
+    }
+
+    Ok(timers)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_timers, BreedingPool, BreedingPoolV2, DEFAULT_BABY_TIMER};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_timers() {
+        assert_eq!(
+            parse_timers(&["3,4,3,1,2".to_string()]).unwrap(),
+            vec![3, 4, 3, 1, 2]
+        );
+        assert_eq!(parse_timers(&["8,0".to_string()]).unwrap(), vec![8, 0]);
+    }
+
+    #[test]
+    fn test_fish_by_timer() {
+        let pool = BreedingPoolV2::new(&[3, 4, 3, 1, 2]).unwrap();
+        assert_eq!(pool.fish_by_timer(), vec![0, 1, 1, 2, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_invalid_timers_are_errors() {
+        assert!(parse_timers(&["9".to_string()]).is_err());
+        assert!(parse_timers(&["3,x".to_string()]).is_err());
+        assert!(parse_timers(&[]).is_err());
+        assert!(BreedingPoolV2::new(&[9]).is_err());
+    }
+
+    #[test]
+    fn test_fish_overflow_is_an_error() {
+        // A single fish's descendants stop fitting in a 64 bit usize after 506 days.
+        let mut pool = BreedingPoolV2::new(&[0]).unwrap();
+        assert!(pool.tick_days(600).is_err());
+
+        let mut pool = BreedingPoolV2::new(&[3, 4]).unwrap();
+        pool.fishies.insert(3, usize::MAX);
+        assert!(pool.total_fish().is_err());
+    }
+
+    #[test]
+    fn test_explains_each_day() {
+        let mut pool = BreedingPool::new(&[1, 0]);
+        let (_, explanations) = capture_explanations(|| pool.tick_days(2));
+        assert_eq!(explanations, vec!["Day 1: 3 fish", "Day 2: 4 fish"]);
+
+        let mut pool = BreedingPoolV2::new(&[1, 0]).unwrap();
+        let (_, explanations) = capture_explanations(|| pool.tick_days(2));
+        assert_eq!(
+            explanations,
+            vec![
+                "Day 1: [1, 0, 0, 0, 0, 0, 1, 0, 1] fish by timer, 3 fish",
+                "Day 2: [0, 0, 0, 0, 0, 1, 1, 1, 1] fish by timer, 4 fish",
+            ]
+        );
+    }
+
+    // BreedingPool simulates every fish, so it's the reference for
+    // BreedingPoolV2 as long as the pool stays small enough to fit in memory.
+    proptest! {
+        #[test]
+        fn test_pool_v2_matches_naive_pool(
+            timers in prop::collection::vec(0..=DEFAULT_BABY_TIMER, 1..20),
+            days in 0..=80usize,
+        ) {
+            let mut pool = BreedingPool::new(&timers);
+            pool.tick_days(days);
+
+            let mut better_pool = BreedingPoolV2::new(&timers).unwrap();
+            better_pool.tick_days(days).unwrap();
+
+            prop_assert_eq!(better_pool.total_fish().unwrap(), pool.total_fish());
+        }
+    }
+}
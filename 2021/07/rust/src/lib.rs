@@ -0,0 +1,240 @@
+//! 2021 day 7: The Treachery of Whales.
+
+use advent_input_parser::{explain, AdventError};
+use log::info;
+use std::convert::TryInto;
+
+/// Parse the single line of comma separated crab positions.
+pub fn parse_positions(input: &[String]) -> Result<Vec<u32>, AdventError> {
+    // This problem only has 1 line of input.
+    let input = input.first().ok_or(AdventError::EmptyInput)?;
+
+    let mut positions: Vec<u32> = Vec::new();
+    for pos in input.split(",") {
+        positions.push(pos.parse::<u32>().map_err(|_| {
+            AdventError::InvalidInput(format!(
+                "Expected an integer no larger than a u32, got {:?}",
+                pos
+            ))
+        })?);
+    }
+
+    Ok(positions)
+}
+
+/// The furthest position any crab starts at.
+pub fn max_position(positions: &[u32]) -> u32 {
+    positions.iter().copied().max().unwrap_or(0)
+}
+
+/// Returns the position that uses the least "fuel"
+/// i.e., the position that is the least cumulative distance from all
+/// values in "start_positions".
+pub fn part_1(start_positions: &[u32], max_position: u32) -> Result<u32, AdventError> {
+    // The cheapest position so far, and its fuel cost.
+    let mut best: Option<(u32, u32)> = None;
+
+    for i in 0..=max_position {
+        let mut distance = 0;
+        for position in start_positions {
+            distance += (i64::from(*position) - i64::from(i)).abs()
+        }
+
+        // A position that needs more fuel than fits in a u32 can't be the answer.
+        let distance: u32 = match distance.try_into() {
+            Ok(distance) => distance,
+            Err(_) => continue,
+        };
+        if best.is_none_or(|(_, min_distance)| distance < min_distance) {
+            explain!(
+                "Position {} costs {} fuel, the cheapest so far",
+                i,
+                distance
+            );
+            best = Some((i, distance));
+        }
+    }
+
+    let (min_distance_position, min_distance) = best.ok_or_else(fuel_overflow)?;
+    info!("Part 1 min_distance_position: {:?}", min_distance_position);
+    explain!(
+        "Cheapest position is {} at {} fuel",
+        min_distance_position,
+        min_distance
+    );
+    Ok(min_distance)
+}
+
+/// Returns the position that uses the most "fuel"
+/// i.e., the position that is the least cumulative distance from all
+/// values in "start_positions". The difference between this and part 1
+/// is that fuel cost for distance is no longer abs(a - b),
+/// and is now sum(1 -> abs(a-b))
+pub fn part_2(start_positions: &[u32], max_position: u32) -> Result<u32, AdventError> {
+    // The cheapest position so far, and its fuel cost.
+    let mut best: Option<(u32, u32)> = None;
+
+    // Create a vector to store the fuel cost for a given distance
+    // We can do this by going from 1 -> X and adding the previous value.
+    // e.g.
+    // > distance_cost = [0, 1, 3, 6, 10]
+    // and arr[4] gives us 10 immediately.
+    // The table stops once a cost no longer fits in a u32. Moving a crab
+    // any further than that can never be part of the answer.
+    let mut distance_cost: Vec<u32> = vec![0];
+    for val in 1..=max_position {
+        match distance_cost.last().unwrap().checked_add(val) {
+            Some(cost) => distance_cost.push(cost),
+            None => break,
+        }
+    }
+
+    for i in 0..=max_position {
+        let distance = start_positions.iter().try_fold(0u32, |total, position| {
+            let distance_length: usize = (i64::from(*position) - i64::from(i))
+                .abs()
+                .try_into()
+                .unwrap();
+            total.checked_add(*distance_cost.get(distance_length)?)
+        });
+
+        // Like part 1, positions whose fuel doesn't fit in a u32 are skipped.
+        if let Some(distance) = distance {
+            if best.is_none_or(|(_, min_distance)| distance < min_distance) {
+                explain!(
+                    "Position {} costs {} fuel, the cheapest so far",
+                    i,
+                    distance
+                );
+                best = Some((i, distance));
+            }
+        }
+    }
+
+    let (min_distance_position, min_distance) = best.ok_or_else(fuel_overflow)?;
+    info!("Part 2 min_distance_position: {:?}", min_distance_position);
+    explain!(
+        "Cheapest position is {} at {} fuel",
+        min_distance_position,
+        min_distance
+    );
+    Ok(min_distance)
+}
+
+fn fuel_overflow() -> AdventError {
+    AdventError::NoSolution("Total fuel cost doesn't fit in a u32".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{max_position, parse_positions, part_1, part_2};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    fn solve(input: &str) -> (u32, u32) {
+        let positions = parse_positions(&[input.to_string()]).unwrap();
+        let max_position = max_position(&positions);
+        (
+            part_1(&positions, max_position).unwrap(),
+            part_2(&positions, max_position).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve("16,1,2,0,4,2,7,1,2,14"), (37, 168));
+    }
+
+    #[test]
+    fn test_max_position_is_a_candidate() {
+        assert_eq!(solve("3,3,3"), (0, 0));
+        assert_eq!(solve("0"), (0, 0));
+    }
+
+    #[test]
+    fn test_far_apart_crabs_still_fit() {
+        // Moving one crab all the way costs more than a u32 holds in part 2,
+        // but meeting in the middle doesn't.
+        assert_eq!(solve("0,100000"), (100000, 2500050000));
+    }
+
+    #[test]
+    fn test_fuel_overflow_is_an_error() {
+        let positions = vec![0, 0, 100000, 100000];
+        assert!(part_2(&positions, 100000).is_err());
+    }
+
+    #[test]
+    fn test_invalid_position_is_an_error() {
+        assert!(parse_positions(&["1,-2".to_string()]).is_err());
+        assert!(parse_positions(&[]).is_err());
+    }
+
+    #[test]
+    fn test_explains_cheaper_positions() {
+        let positions = vec![0, 1, 5];
+
+        let (_, explanations) = capture_explanations(|| part_1(&positions, 5));
+        assert_eq!(
+            explanations,
+            vec![
+                "Position 0 costs 6 fuel, the cheapest so far",
+                "Position 1 costs 5 fuel, the cheapest so far",
+                "Cheapest position is 1 at 5 fuel",
+            ]
+        );
+
+        let (_, explanations) = capture_explanations(|| part_2(&positions, 5));
+        assert_eq!(
+            explanations,
+            vec![
+                "Position 0 costs 16 fuel, the cheapest so far",
+                "Position 1 costs 11 fuel, the cheapest so far",
+                "Position 2 costs 10 fuel, the cheapest so far",
+                "Cheapest position is 2 at 10 fuel",
+            ]
+        );
+    }
+
+    fn fuel_to(target: i64, positions: &[u32], cost: fn(i64) -> i64) -> i64 {
+        positions
+            .iter()
+            .map(|position| cost((i64::from(*position) - target).abs()))
+            .sum()
+    }
+
+    // Closed form reference for part 1: the median minimizes the total distance.
+    fn median_fuel(positions: &[u32]) -> i64 {
+        let mut sorted = positions.to_vec();
+        sorted.sort_unstable();
+        fuel_to(i64::from(sorted[sorted.len() / 2]), positions, |distance| {
+            distance
+        })
+    }
+
+    // Closed form reference for part 2: the best real position is within 1/2 of the
+    // mean, so the best whole one is between 1 below its floor and 1 above its ceiling.
+    fn mean_fuel(positions: &[u32]) -> i64 {
+        let sum: i64 = positions.iter().map(|position| i64::from(*position)).sum();
+        let mean = sum / positions.len() as i64;
+        (mean - 1..=mean + 2)
+            .map(|target| fuel_to(target, positions, |distance| distance * (distance + 1) / 2))
+            .min()
+            .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn test_scan_matches_closed_forms(positions in prop::collection::vec(0..2000u32, 1..50)) {
+            let max_position = max_position(&positions);
+            prop_assert_eq!(
+                i64::from(part_1(&positions, max_position).unwrap()),
+                median_fuel(&positions)
+            );
+            prop_assert_eq!(
+                i64::from(part_2(&positions, max_position).unwrap()),
+                mean_fuel(&positions)
+            );
+        }
+    }
+}
@@ -1,99 +1,44 @@
-use advent_input_parser::parse_by_line;
-use std::{convert::TryInto, io};
+//! Solves 2021 day 7: The Treachery of Whales. Reads the puzzle input from stdin and prints both answers.
 
-fn main() {
-    let parsed_input = parse_by_line(io::stdin().lock());
-    // This problem only has 1 line of input.
-    let parsed_input = parsed_input
-        .get(0)
-        .expect("One line of input was expected!");
-
-    let mut max_position: u32 = 0;
-    let mut initial_positions: Vec<u32> = Vec::new();
-
-    for pos in parsed_input.split(",") {
-        let pos_int = pos
-            .parse::<u32>()
-            .expect("Expected an integer no larger than a u32.");
-        initial_positions.push(pos_int);
-        max_position = max_position.max(pos_int);
-    }
-
-    println!("Max position: {:?}", max_position);
-
-    let part_1_position = part_1(&initial_positions, max_position);
-    println!("Part 1 answer: {:?}", part_1_position);
-
-    let part_2_position = part_2(&initial_positions, max_position);
-    println!("Part 2 answer: {:?}", part_2_position);
-}
+use advent_2021_day07::{max_position, parse_positions, part_1, part_2};
+use advent_input_parser::{
+    init_explain, init_logging, init_timing, parse_by_line, report_timing, time_span, AdventError,
+};
+use log::debug;
+use std::{io, process};
 
-// Returns the position that uses the least "fuel"
-// i.e., the position that is the least cumulative distance from all
-// values in "start_positions".
-fn part_1(start_positions: &Vec<u32>, max_position: u32) -> u32 {
-    let mut min_distance = u32::MAX;
-    let mut min_distance_position = u32::MAX;
+fn main() {
+    init_logging();
+    init_timing();
+    init_explain();
 
-    for i in 0..max_position {
-        let mut distance = 0;
-        for position in start_positions {
-            distance += (i64::from(*position) - i64::from(i)).abs()
-        }
+    let result = run();
+    report_timing();
 
-        let distance: u32 = distance.try_into().unwrap();
-        if distance < min_distance {
-            min_distance = distance;
-            min_distance_position = i;
-        }
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
     }
-
-    println!("Part 1 min_distance_position: {:?}", min_distance_position);
-    min_distance
 }
 
-// Returns the position that uses the most "fuel"
-// i.e., the position that is the least cumulative distance from all
-// values in "start_positions". The difference between this and part 1
-// is that fuel cost for distance is no longer abs(a - b),
-// and is now sum(1 -> abs(a-b))
-fn part_2(start_positions: &Vec<u32>, max_position: u32) -> u32 {
-    let mut min_distance = u32::MAX;
-    let mut min_distance_position = u32::MAX;
+fn run() -> Result<(), AdventError> {
+    let parse_span = time_span("parse");
+    let parsed_input = parse_by_line(io::stdin().lock())?;
+    let initial_positions = parse_positions(&parsed_input)?;
+    let max_position = max_position(&initial_positions);
+    drop(parse_span);
 
-    // Create a vector to store the fuel cost for a given distance
-    // We can do this by initializing a vector from 1 -> X
-    // and then going from 2 -> X and adding the previous value.
-    // e.g.
-    // > distance_cost = [0, 1, 2, 3, 4]
-    // becomes
-    // > distance_cost = [0, 1, 3, 6, 10]
-    // and arr[4] gives us 10 immediately.
-    let distance_cost_base: Vec<u32> = (0..max_position + 1).collect();
-    let mut distance_cost: Vec<u32> = Vec::new();
-    distance_cost.push(0);
-    for val in distance_cost_base.into_iter().skip(1) {
-        let last_val = *distance_cost.last().unwrap();
-        distance_cost.push(val + last_val);
-    }
+    debug!("Max position: {:?}", max_position);
 
-    for i in 0..max_position {
-        let mut distance: u32 = 0;
-        for position in start_positions {
-            let distance_length: usize = (i64::from(*position) - i64::from(i))
-                .abs()
-                .try_into()
-                .unwrap();
-            distance += distance_cost[distance_length];
-        }
+    let part_1_span = time_span("part 1");
+    let part_1_position = part_1(&initial_positions, max_position)?;
+    println!("Part 1 answer: {:?}", part_1_position);
+    drop(part_1_span);
 
-        let distance: u32 = distance.try_into().unwrap();
-        if distance < min_distance {
-            min_distance = distance;
-            min_distance_position = i;
-        }
-    }
+    let part_2_span = time_span("part 2");
+    let part_2_position = part_2(&initial_positions, max_position)?;
+    println!("Part 2 answer: {:?}", part_2_position);
+    drop(part_2_span);
 
-    println!("Part 2 min_distance_position: {:?}", min_distance_position);
-    min_distance
+    Ok(())
 }
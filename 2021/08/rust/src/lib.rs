@@ -0,0 +1,404 @@
+//! 2021 day 8: Seven Segment Search.
+
+use advent_input_parser::{explain, time_span, AdventError};
+use log::trace;
+use std::collections::{HashMap, HashSet};
+
+/// Splits a line into the 10 patterns before the "|" and the 4 output digits after it.
+pub fn split_entry(line: &str) -> Result<(&str, &str), AdventError> {
+    let (patterns, output) = line.split_once('|').ok_or_else(|| {
+        AdventError::InvalidInput(format!(
+            "Expected a line of the form 'patterns | output', got {:?}",
+            line
+        ))
+    })?;
+
+    let pattern_count = patterns.trim().split(" ").count();
+    let output_count = output.trim().split(" ").count();
+    if pattern_count != 10 || output_count != 4 {
+        return Err(AdventError::InvalidInput(format!(
+            "Expected 10 patterns and 4 output digits, got {} and {} in {:?}",
+            pattern_count, output_count, line
+        )));
+    }
+
+    Ok((patterns, output))
+}
+
+/// Counts the amount of strings that are of length 2, 3, 4, or 7
+/// in the output half of each line.
+pub fn part_1(input_lines: &[String]) -> Result<usize, AdventError> {
+    let mut unique_segment_digits = 0;
+
+    for line in input_lines {
+        let (_, second_half) = split_entry(line)?;
+
+        for digits in second_half.split(" ") {
+            if digits.len() == 2 || digits.len() == 3 || digits.len() == 4 || digits.len() == 7 {
+                unique_segment_digits += 1;
+            }
+        }
+    }
+
+    Ok(unique_segment_digits)
+}
+
+/// Decodes every line and adds up their output values.
+pub fn part_2(input_lines: &[String]) -> Result<usize, AdventError> {
+    let mut total_value = 0;
+
+    for line in input_lines {
+        total_value += decode_line(line)?;
+    }
+
+    Ok(total_value)
+}
+
+// These confusingly written rules are the way to decode any given number
+// Using numbers 1, 4, 7, and 8, these rules + the length of the string
+// can be used to determine what each other number is.
+
+// 0: Shares all letters with 1 + 7, 3/4 of 4's letters, 6 letters total
+// 1: (Unique) 2 letters
+// 2: Shares 1 letter with 1, 2 letters with 7, 2 letters with 4, 5 letters total
+// 3: Shares all letters with 1 + 7, 3/4 of 4's letters, 5 letters total
+// 4: (Unique) 4 letters - Shares 2 letters with 1
+// 5: Shares 1 letter with 1, 2 letters with 7, 3 letters with 4, 5 letters total
+// 6: Shares 1 letter with 1, 2 letters with 7, 3 letters with 4, 6 letters total
+// 7: (Unique) 3 letters - Shares 2 letters with 1
+// 8: (Unique) 7 letters
+// 9: Shares all letters with 1 + 4 + 7, 6 letters total
+
+/// Works out which pattern is which digit from the first half of a line,
+/// and returns the 4 digit number shown in the second half.
+pub fn decode_line(line: &str) -> Result<usize, AdventError> {
+    let (first_half, second_half) = split_entry(line)?;
+    // Incredibly ugly, but this turns a Vec like ["eb", "bcdfgea"] into ["be", "abcdefg"]
+    // so that the order of letters is consistent between input/output.
+    let first_half: Vec<String> = first_half
+        .trim()
+        .split(" ")
+        .map(|e| {
+            let mut letters: Vec<char> = e.chars().collect();
+            letters.sort_unstable();
+            letters.into_iter().collect()
+        })
+        .collect();
+    let second_half: Vec<String> = second_half
+        .trim()
+        .split(" ")
+        .map(|e| {
+            let mut letters: Vec<char> = e.chars().collect();
+            letters.sort_unstable();
+            letters.into_iter().collect()
+        })
+        .collect();
+
+    trace!("{:?}, {:?}", first_half, second_half);
+    explain!("Decoding {}", line.trim());
+
+    let mut digit_map: HashMap<usize, &String> = HashMap::new();
+    let mut value_map: HashMap<&String, usize> = HashMap::new();
+
+    // First pass: Get 1, 4, 7 and 8 into our hashmap ahead of pass 2.
+    let first_pass_span = time_span("first pass");
+    for value in first_half.iter() {
+        match value.len() {
+            2 => {
+                digit_map.insert(1, value);
+                value_map.insert(value, 1);
+                explain!("{:<7} is 1: the only pattern with 2 segments", value);
+            }
+            3 => {
+                digit_map.insert(7, value);
+                value_map.insert(value, 7);
+                explain!("{:<7} is 7: the only pattern with 3 segments", value);
+            }
+            4 => {
+                digit_map.insert(4, value);
+                value_map.insert(value, 4);
+                explain!("{:<7} is 4: the only pattern with 4 segments", value);
+            }
+            7 => {
+                digit_map.insert(8, value);
+                value_map.insert(value, 8);
+                explain!("{:<7} is 8: the only pattern with 7 segments", value);
+            }
+            _ => (),
+        }
+    }
+    drop(first_pass_span);
+
+    let second_pass_span = time_span("second pass");
+    for value in first_half.iter() {
+        match value.len() {
+            5 => {
+                // 2: Shares 1 letter with 1, 2 letters with 7, 2 letters with 4, 5 letters total
+                // 3: Shares all letters with 1 + 7, 3 of 4's letters, 5 letters total
+                // 5: Shares 1 letter with 1, 2 letters with 7, 3 letters with 4, 5 letters total
+
+                // Note: The only difference between 2 and 3+5 is the amount of letters they share with 4.
+                let four_value = digit_map.get(&4).ok_or_else(|| missing_digit(4, line))?;
+                let amount_shared = shared_chars(value, four_value);
+                if amount_shared == 2 {
+                    digit_map.insert(2, value);
+                    value_map.insert(value, 2);
+                    explain!("{:<7} is 2: 5 segments, and shares 2 with 4", value);
+                } else if amount_shared == 3 {
+                    let seven_value = digit_map.get(&7).ok_or_else(|| missing_digit(7, line))?;
+                    let amount_shared_seven = shared_chars(value, seven_value);
+                    // The difference between 3 and 5 is the number of digits shared with 7
+                    if amount_shared_seven == seven_value.len() {
+                        digit_map.insert(3, value);
+                        value_map.insert(value, 3);
+                        explain!(
+                            "{:<7} is 3: 5 segments, shares 3 with 4 and all of 7",
+                            value
+                        );
+                    } else {
+                        digit_map.insert(5, value);
+                        value_map.insert(value, 5);
+                        explain!(
+                            "{:<7} is 5: 5 segments, shares 3 with 4 but not all of 7",
+                            value
+                        );
+                    }
+                } else {
+                    return Err(AdventError::InvalidInput(format!(
+                        "Unexpected amount_shared in 5 branch: {:?}",
+                        amount_shared
+                    )));
+                }
+            }
+            6 => {
+                // 0: Shares all letters with 1 + 7, 3 of 4's letters, 6 letters total
+                // 6: Shares 1 letter with 1, 2 letters with 7, 3 letters with 4, 6 letters total
+                // 9: Shares all letters with 1 + 4 + 7, 6 letters total
+
+                // If all letters are shared with 4, it must be a 9
+                let four_value = digit_map.get(&4).ok_or_else(|| missing_digit(4, line))?;
+                let amount_shared = shared_chars(value, four_value);
+                if amount_shared == four_value.len() {
+                    digit_map.insert(9, value);
+                    value_map.insert(value, 9);
+                    explain!("{:<7} is 9: 6 segments, and shares all of 4", value);
+                } else {
+                    // Now, if all of 7's letters are shared, it must be a 0
+                    let seven_value = digit_map.get(&7).ok_or_else(|| missing_digit(7, line))?;
+                    let amount_shared = shared_chars(value, seven_value);
+                    if amount_shared == seven_value.len() {
+                        digit_map.insert(0, value);
+                        value_map.insert(value, 0);
+                        explain!(
+                            "{:<7} is 0: 6 segments, shares all of 7 but not all of 4",
+                            value
+                        );
+                    } else {
+                        // If not all of 7s letters are shared, it must be a 6
+                        digit_map.insert(6, value);
+                        value_map.insert(value, 6);
+                        explain!(
+                            "{:<7} is 6: 6 segments, and shares all of neither 4 nor 7",
+                            value
+                        );
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    drop(second_pass_span);
+
+    // Now, we can look at second_half and figure out what each number is.
+    // second_half is always 4 digits, so to get total_value, the first number is multiplied by 1000, then 100, then 10, then 1
+    let mut output_value = 0;
+    let mut multiplier: usize = 1000;
+    for value in second_half {
+        let number = value_map.get(&value).ok_or_else(|| {
+            AdventError::InvalidInput(format!(
+                "Output {:?} doesn't match any pattern in {:?}",
+                value, line
+            ))
+        })?;
+        trace!("Value for {:?}: {:?}", value, number);
+        output_value += number * multiplier;
+        multiplier /= 10;
+    }
+    explain!("Output value is {}", output_value);
+
+    Ok(output_value)
+}
+
+// 1, 4 and 7 are needed to figure out the other digits, so a line without them can't be decoded.
+fn missing_digit(digit: usize, line: &str) -> AdventError {
+    AdventError::InvalidInput(format!(
+        "No pattern for the digit {:?} found in {:?}",
+        digit, line
+    ))
+}
+
+fn shared_chars(str_1: &str, str_2: &str) -> usize {
+    let chars_1: HashSet<char> = str_1.chars().collect();
+    let chars_2: HashSet<char> = str_2.chars().collect();
+
+    chars_1.intersection(&chars_2).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{decode_line, part_1};
+    use advent_input_parser::capture_explanations;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_decode_line() {
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(decode_line(line).unwrap(), 5353);
+    }
+
+    #[test]
+    fn test_explains_each_digit() {
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        let (_, explanations) = capture_explanations(|| decode_line(line));
+
+        // The line, one explanation per digit, then the output value.
+        assert_eq!(explanations.len(), 12);
+        assert_eq!(explanations[0], format!("Decoding {}", line));
+        assert!(
+            explanations.contains(&"ab      is 1: the only pattern with 2 segments".to_string())
+        );
+        assert_eq!(explanations[11], "Output value is 5353");
+    }
+
+    #[test]
+    fn test_invalid_lines_are_errors() {
+        // No "|" between the patterns and the output.
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab cdfeb fcadb";
+        assert!(decode_line(line).is_err());
+        assert!(part_1(&[line.to_string()]).is_err());
+
+        // Too few or too many output digits.
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb";
+        assert!(decode_line(line).is_err());
+        assert!(part_1(&[line.to_string()]).is_err());
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf ab ab";
+        assert!(decode_line(line).is_err());
+
+        // Only 9 patterns.
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert!(decode_line(line).is_err());
+
+        // No pattern with 4 segments, so 4 can't be found.
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert!(decode_line(line).is_err());
+
+        // "abc" isn't one of the patterns.
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb abc";
+        assert!(decode_line(line).is_err());
+    }
+
+    // The segments each digit lights up when the display is wired correctly.
+    static DIGITS: [&str; 10] = [
+        "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+    ];
+
+    // Segments as bits, so a pattern's letter order doesn't matter.
+    fn segments(pattern: &str) -> u8 {
+        pattern
+            .bytes()
+            .fold(0, |segments, letter| segments | 1 << (letter - b'a'))
+    }
+
+    fn wire(segments: u8, wiring: &[usize]) -> u8 {
+        (0..7)
+            .filter(|segment| segments & 1 << segment != 0)
+            .fold(0, |wired, segment| wired | 1 << wiring[segment])
+    }
+
+    // Every way to wire 7 segments, using Heap's algorithm.
+    fn wirings() -> Vec<[usize; 7]> {
+        let mut wiring = [0, 1, 2, 3, 4, 5, 6];
+        let mut counters = [0; 7];
+        let mut all = vec![wiring];
+
+        let mut i = 1;
+        while i < 7 {
+            if counters[i] < i {
+                let swap_with = if i % 2 == 0 { 0 } else { counters[i] };
+                wiring.swap(swap_with, i);
+                all.push(wiring);
+                counters[i] += 1;
+                i = 1;
+            } else {
+                counters[i] = 0;
+                i += 1;
+            }
+        }
+
+        all
+    }
+
+    // Brute force reference: try all 7! ways the segments could be wired, and decode the
+    // output with the one wiring that turns the patterns into exactly the 10 digits.
+    fn brute_force(line: &str) -> Option<usize> {
+        let (patterns, output) = line.split_once(" | ")?;
+        let mut patterns: Vec<u8> = patterns.split(' ').map(segments).collect();
+        patterns.sort_unstable();
+
+        let wired_digits = wirings().into_iter().find_map(|wiring| {
+            let mut wired_digits = [0; 10];
+            for (wired, digit) in wired_digits.iter_mut().zip(DIGITS.iter()) {
+                *wired = wire(segments(digit), &wiring);
+            }
+            let mut sorted = wired_digits;
+            sorted.sort_unstable();
+            Some(wired_digits).filter(|_| sorted[..] == patterns[..])
+        })?;
+
+        output.split(' ').try_fold(0, |value, digit| {
+            let digit = wired_digits
+                .iter()
+                .position(|wired| *wired == segments(digit))?;
+            Some(value * 10 + digit)
+        })
+    }
+
+    // A line for a randomly wired display. The patterns are shuffled, and so are the
+    // letters in each pattern and output digit.
+    fn line_strategy() -> impl Strategy<Value = String> {
+        let order = Just((0..7).collect::<Vec<usize>>()).prop_shuffle();
+        (
+            order.clone(),
+            Just((0..10).collect::<Vec<usize>>()).prop_shuffle(),
+            prop::collection::vec(0..10usize, 4),
+            prop::collection::vec(order, 14),
+        )
+            .prop_map(|(wiring, patterns, output, letter_orders)| {
+                let digits: Vec<String> = patterns
+                    .into_iter()
+                    .chain(output)
+                    .zip(letter_orders)
+                    .map(|(digit, letter_order)| {
+                        let wired = wire(segments(DIGITS[digit]), &wiring);
+                        letter_order
+                            .into_iter()
+                            .filter(|segment| wired & 1 << segment != 0)
+                            .map(|segment| (b'a' + segment as u8) as char)
+                            .collect()
+                    })
+                    .collect();
+                format!("{} | {}", digits[..10].join(" "), digits[10..].join(" "))
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_decode_matches_brute_force(line in line_strategy()) {
+            prop_assert_eq!(decode_line(&line).ok(), brute_force(&line));
+        }
+    }
+}
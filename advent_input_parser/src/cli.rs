@@ -0,0 +1,109 @@
+use std::env;
+
+// The command line flags every day understands. They're all parsed here, in one pass,
+// so the value of one flag (e.g. the path after --trace) is never read as another flag.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Flags {
+    // How many times -v was passed (-vv counts as 2).
+    pub(crate) verbosity: usize,
+    pub(crate) quiet: bool,
+    pub(crate) timings: bool,
+    pub(crate) trace_path: Option<String>,
+    // Set when --trace was passed without a path after it.
+    pub(crate) trace_missing_path: bool,
+    pub(crate) explain: bool,
+}
+
+// Flags from the command line of the current binary.
+pub(crate) fn flags() -> Flags {
+    parse_flags(env::args().skip(1))
+}
+
+pub(crate) fn parse_flags<I>(args: I) -> Flags
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut flags = Flags::default();
+    let mut args = args.into_iter().peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-q" | "--quiet" => flags.quiet = true,
+            "--verbose" => flags.verbosity += 1,
+            "--timings" => flags.timings = true,
+            "--explain" => flags.explain = true,
+            "--trace" => match args.next_if(|next| !next.starts_with('-')) {
+                Some(path) => flags.trace_path = Some(path),
+                None => flags.trace_missing_path = true,
+            },
+            _ if arg.starts_with("-v") && arg[1..].chars().all(|c| c == 'v') => {
+                flags.verbosity += arg.len() - 1
+            }
+            _ => {
+                if let Some(path) = arg.strip_prefix("--trace=") {
+                    if path.is_empty() {
+                        flags.trace_missing_path = true;
+                    } else {
+                        flags.trace_path = Some(path.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    flags
+}
+
+#[cfg(test)]
+pub(crate) fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{args, parse_flags, Flags};
+
+    #[test]
+    fn test_no_flags() {
+        assert_eq!(parse_flags(args(&[])), Flags::default());
+    }
+
+    #[test]
+    fn test_all_flags() {
+        assert_eq!(
+            parse_flags(args(&[
+                "-vv",
+                "--verbose",
+                "-q",
+                "--timings",
+                "--trace",
+                "out.json",
+                "--explain",
+            ])),
+            Flags {
+                verbosity: 3,
+                quiet: true,
+                timings: true,
+                trace_path: Some("out.json".to_string()),
+                trace_missing_path: false,
+                explain: true,
+            }
+        );
+        assert_eq!(
+            parse_flags(args(&["--trace=out.json"])).trace_path,
+            Some("out.json".to_string())
+        );
+    }
+
+    #[test]
+    fn test_trace_without_a_path() {
+        // The flag after --trace is still read as a flag, not as the path.
+        let flags = parse_flags(args(&["--trace", "-q"]));
+        assert_eq!(flags.trace_path, None);
+        assert!(flags.trace_missing_path);
+        assert!(flags.quiet);
+
+        assert!(parse_flags(args(&["-v", "--trace"])).trace_missing_path);
+        assert!(parse_flags(args(&["--trace="])).trace_missing_path);
+    }
+}
@@ -0,0 +1,41 @@
+use std::{error, fmt, io};
+
+/// Shared error type for every day, so main can print a message and exit
+/// instead of panicking on bad input.
+#[derive(Debug)]
+pub enum AdventError {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// There was no input to work with.
+    EmptyInput,
+    /// Some of the input couldn't be understood. Holds a description of the problem.
+    InvalidInput(String),
+    /// The input was valid, but the puzzle has no answer for it.
+    NoSolution(String),
+}
+
+impl fmt::Display for AdventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdventError::Io(err) => write!(f, "Problem reading input: {}", err),
+            AdventError::EmptyInput => write!(f, "Input was empty"),
+            AdventError::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+            AdventError::NoSolution(message) => write!(f, "No solution found: {}", message),
+        }
+    }
+}
+
+impl error::Error for AdventError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AdventError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AdventError {
+    fn from(err: io::Error) -> AdventError {
+        AdventError::Io(err)
+    }
+}
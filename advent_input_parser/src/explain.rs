@@ -0,0 +1,141 @@
+use crate::cli;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static EXPLAIN_ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Set while capture_explanations runs. Explanations are collected here instead of printed.
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Reads --explain to decide whether days narrate how they got their answers.
+pub fn init_explain() {
+    if cli::flags().explain {
+        EXPLAIN_ENABLED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Whether --explain was passed (or capture_explanations is running).
+/// explain! checks this before formatting anything.
+pub fn explain_enabled() -> bool {
+    EXPLAIN_ENABLED.load(Ordering::Relaxed) || CAPTURED.with(|captured| captured.borrow().is_some())
+}
+
+/// Runs `f` with explanations turned on for the current thread, and returns
+/// what it explained instead of printing it. Useful for testing a day's narration.
+pub fn capture_explanations<F, R>(f: F) -> (R, Vec<String>)
+where
+    F: FnOnce() -> R,
+{
+    let _restore = RestoreCaptured(CAPTURED.with(|captured| captured.replace(Some(Vec::new()))));
+    let result = f();
+    let explanations = CAPTURED.with(|captured| captured.borrow_mut().take());
+
+    (result, explanations.unwrap_or_default())
+}
+
+// Puts back whatever capture_explanations replaced when it's dropped, so a panic
+// in the captured function doesn't leave the thread collecting explanations.
+struct RestoreCaptured(Option<Vec<String>>);
+
+impl Drop for RestoreCaptured {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CAPTURED.with(|captured| captured.replace(previous));
+    }
+}
+
+// The sink behind explain!. Explanations go to stderr so that stdout
+// still only has the answers.
+#[doc(hidden)]
+pub fn write_explanation(args: fmt::Arguments) {
+    let captured = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(explanations) => {
+            explanations.push(args.to_string());
+            true
+        }
+        None => false,
+    });
+
+    if !captured {
+        eprintln!("{}", args);
+    }
+}
+
+/// Narrates a step of a solution when --explain is passed. Takes the same
+/// arguments as format!, which are only evaluated when explaining:
+///
+/// ```
+/// use advent_input_parser::explain;
+///
+/// let (position, bit) = (1, 0);
+/// explain!("Bit {}: keeping numbers with a {}", position, bit);
+/// ```
+#[macro_export]
+macro_rules! explain {
+    ($($arg:tt)+) => {
+        if $crate::explain_enabled() {
+            $crate::write_explanation(format_args!($($arg)+));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::explain::{capture_explanations, explain_enabled};
+    use std::panic;
+
+    #[test]
+    fn test_capture_explanations() {
+        assert!(!explain_enabled());
+
+        let (answer, explanations) = capture_explanations(|| {
+            explain!("First step: {}", 1);
+            explain!("Second step: {}", 2);
+            3
+        });
+
+        assert_eq!(answer, 3);
+        assert_eq!(explanations, vec!["First step: 1", "Second step: 2"]);
+        assert!(!explain_enabled());
+    }
+
+    #[test]
+    fn test_arguments_are_only_evaluated_when_explaining() {
+        let mut evaluated = false;
+        explain!("{}", {
+            evaluated = true;
+            "not explaining"
+        });
+
+        assert!(!evaluated);
+    }
+
+    #[test]
+    fn test_capture_is_undone_after_a_panic() {
+        let result = panic::catch_unwind(|| {
+            capture_explanations(|| {
+                explain!("About to panic");
+                panic!("the captured function panicked");
+            })
+        });
+
+        assert!(result.is_err());
+        assert!(!explain_enabled());
+    }
+
+    #[test]
+    fn test_nested_captures() {
+        let (inner, outer) = capture_explanations(|| {
+            explain!("Outer");
+            let (_, inner) = capture_explanations(|| explain!("Inner"));
+            explain!("Outer again");
+            inner
+        });
+
+        assert_eq!(inner, vec!["Inner"]);
+        assert_eq!(outer, vec!["Outer", "Outer again"]);
+    }
+}
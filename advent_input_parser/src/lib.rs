@@ -1,6 +1,20 @@
+//! Input parsing, errors, logging, timing and `--explain` support shared by every day.
+
 use std::io::BufRead;
 
-pub fn parse_by_line<R>(mut reader: R) -> Vec<String>
+mod cli;
+mod error;
+mod explain;
+mod logging;
+mod timing;
+
+pub use error::AdventError;
+pub use explain::{capture_explanations, explain_enabled, init_explain, write_explanation};
+pub use logging::init_logging;
+pub use timing::{init_timing, report_timing, time_span, Span};
+
+/// Reads the input one line at a time, with surrounding whitespace trimmed.
+pub fn parse_by_line<R>(mut reader: R) -> Result<Vec<String>, AdventError>
 where
     R: BufRead,
 {
@@ -8,9 +22,7 @@ where
     loop {
         let mut input = String::new();
 
-        let result = reader
-            .read_line(&mut input)
-            .expect("Problem reading stdin lines!");
+        let result = reader.read_line(&mut input)?;
 
         // This occurs when EOF has been reached
         if result == 0 {
@@ -20,12 +32,12 @@ where
         read_lines.push(input.trim().to_string());
     }
 
-    read_lines
+    Ok(read_lines)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parse_by_line;
+    use crate::{parse_by_line, AdventError};
 
     // Short explanation for future tyler:
     // &input[..] turns our array of u8 into a slice, which
@@ -37,7 +49,7 @@ mod tests {
     fn test_single_line_input() {
         let input = b"Hello";
         let expected_result = vec!["Hello".to_string()];
-        let actual_result = parse_by_line(&input[..]);
+        let actual_result = parse_by_line(&input[..]).unwrap();
 
         assert_eq!(actual_result, expected_result);
     }
@@ -51,7 +63,7 @@ mod tests {
             String::from("Is"),
             String::from("Up"),
         ];
-        let actual_result = parse_by_line(&input[..]);
+        let actual_result = parse_by_line(&input[..]).unwrap();
 
         assert_eq!(actual_result, expected_result);
     }
@@ -60,8 +72,16 @@ mod tests {
     fn test_lines_are_trimmed() {
         let input = b"Yo\tDawg\t\nSup   ";
         let expected_result = vec![String::from("Yo\tDawg"), String::from("Sup")];
-        let actual_result = parse_by_line(&input[..]);
+        let actual_result = parse_by_line(&input[..]).unwrap();
 
         assert_eq!(actual_result, expected_result);
     }
+
+    #[test]
+    fn test_invalid_utf8_is_an_error() {
+        let input = b"Hello\n\xff\xfe";
+        let actual_result = parse_by_line(&input[..]);
+
+        assert!(matches!(actual_result, Err(AdventError::Io(_))));
+    }
 }
@@ -0,0 +1,66 @@
+use crate::cli::{self, Flags};
+use log::LevelFilter;
+
+// Environment variable used to set log filters, using env_logger's syntax.
+// e.g. AOC_LOG=debug cargo run < ../input
+static LOG_ENV_VAR: &str = "AOC_LOG";
+
+/// Sets up logging for a day's binary. Logs go to stderr so stdout only has answers.
+/// By default only warnings and errors are shown. AOC_LOG can change that, and
+/// -v/-vv/-vvv or -q on the command line win over both.
+pub fn init_logging() {
+    let mut builder = env_logger::Builder::new();
+    builder
+        .filter_level(LevelFilter::Warn)
+        .format_timestamp(None)
+        .parse_env(env_logger::Env::new().filter(LOG_ENV_VAR));
+
+    if let Some(level) = level_from_flags(&cli::flags()) {
+        builder.filter_level(level);
+    }
+
+    builder.init();
+}
+
+// Returns the log level requested by -v (repeatable) or -q, if either was passed.
+fn level_from_flags(flags: &Flags) -> Option<LevelFilter> {
+    if flags.quiet {
+        return Some(LevelFilter::Error);
+    }
+
+    match flags.verbosity {
+        0 => None,
+        1 => Some(LevelFilter::Info),
+        2 => Some(LevelFilter::Debug),
+        _ => Some(LevelFilter::Trace),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{args, parse_flags};
+    use crate::logging;
+    use log::LevelFilter;
+
+    fn level_from_args(values: &[&str]) -> Option<LevelFilter> {
+        logging::level_from_flags(&parse_flags(args(values)))
+    }
+
+    #[test]
+    fn test_no_flags_uses_default() {
+        assert_eq!(level_from_args(&[]), None);
+    }
+
+    #[test]
+    fn test_verbosity_flags() {
+        assert_eq!(level_from_args(&["-v"]), Some(LevelFilter::Info));
+        assert_eq!(level_from_args(&["-vv"]), Some(LevelFilter::Debug));
+        assert_eq!(level_from_args(&["-v", "-v"]), Some(LevelFilter::Debug));
+        assert_eq!(level_from_args(&["-vvvv"]), Some(LevelFilter::Trace));
+    }
+
+    #[test]
+    fn test_quiet_wins_over_verbose() {
+        assert_eq!(level_from_args(&["-vv", "-q"]), Some(LevelFilter::Error));
+    }
+}
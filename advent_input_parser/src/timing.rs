@@ -0,0 +1,352 @@
+use crate::cli::{self, Flags};
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// Environment variable that can be used instead of --trace <path>.
+static TRACE_ENV_VAR: &str = "AOC_TRACE";
+
+static TIMING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static TIMINGS: RefCell<Timings> = RefCell::new(Timings::new());
+}
+
+// What to do with the recorded spans once the day is done.
+#[derive(Debug, Default, PartialEq)]
+struct TimingOptions {
+    print_tree: bool,
+    trace_path: Option<String>,
+}
+
+#[derive(Debug)]
+struct SpanRecord {
+    name: &'static str,
+    parent: Option<usize>,
+    // Offset from when timing was set up.
+    start: Duration,
+    duration: Duration,
+}
+
+struct Timings {
+    options: TimingOptions,
+    epoch: Instant,
+    records: Vec<SpanRecord>,
+    // Indexes into records of the spans that haven't finished yet, innermost last.
+    open_spans: Vec<usize>,
+}
+
+impl Timings {
+    fn new() -> Timings {
+        Timings {
+            options: TimingOptions::default(),
+            epoch: Instant::now(),
+            records: Vec::new(),
+            open_spans: Vec::new(),
+        }
+    }
+}
+
+/// A timed section of a day. The span ends when this is dropped,
+/// so hold onto it for as long as the section runs:
+///
+/// ```
+/// use advent_input_parser::time_span;
+///
+/// let _span = time_span("part 1");
+/// ```
+pub struct Span {
+    index: Option<usize>,
+    start: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(index) = self.index {
+            let duration = self.start.elapsed();
+            TIMINGS.with(|timings| {
+                let mut timings = timings.borrow_mut();
+                timings.records[index].duration = duration;
+                timings.open_spans.retain(|open| *open != index);
+            });
+        }
+    }
+}
+
+/// Reads `--timings` and `--trace <path>` (or `AOC_TRACE`) to decide whether spans are recorded.
+/// Nothing is recorded unless one of them is set.
+pub fn init_timing() {
+    let flags = cli::flags();
+    if flags.trace_missing_path {
+        log::warn!("--trace needs a path to write the trace to, e.g. --trace out.json");
+    }
+
+    let mut options = options_from_flags(&flags);
+    if options.trace_path.is_none() {
+        options.trace_path = env::var(TRACE_ENV_VAR).ok().filter(|path| !path.is_empty());
+    }
+
+    if options.print_tree || options.trace_path.is_some() {
+        TIMING_ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    TIMINGS.with(|timings| {
+        let mut timings = timings.borrow_mut();
+        timings.options = options;
+        timings.epoch = Instant::now();
+    });
+}
+
+/// Starts a span nested inside whichever span is currently open.
+pub fn time_span(name: &'static str) -> Span {
+    let start = Instant::now();
+    if !TIMING_ENABLED.load(Ordering::Relaxed) {
+        return Span { index: None, start };
+    }
+
+    let index = TIMINGS.with(|timings| {
+        let mut timings = timings.borrow_mut();
+        let index = timings.records.len();
+        let record = SpanRecord {
+            name,
+            parent: timings.open_spans.last().copied(),
+            start: start.duration_since(timings.epoch),
+            duration: Duration::default(),
+        };
+        timings.records.push(record);
+        timings.open_spans.push(index);
+        index
+    });
+
+    Span {
+        index: Some(index),
+        start,
+    }
+}
+
+/// Prints the span tree to stderr and/or writes the Chrome trace, depending on how
+/// init_timing was configured. Called once the day has finished.
+pub fn report_timing() {
+    TIMINGS.with(|timings| {
+        let timings = timings.borrow();
+        if timings.options.print_tree {
+            eprint!("{}", timing_tree(&timings.records));
+        }
+
+        if let Some(path) = &timings.options.trace_path {
+            if let Err(err) = fs::write(path, chrome_trace(&timings.records)) {
+                log::warn!("Couldn't write trace to {:?}: {}", path, err);
+            }
+        }
+    });
+}
+
+fn options_from_flags(flags: &Flags) -> TimingOptions {
+    TimingOptions {
+        print_tree: flags.timings,
+        trace_path: flags.trace_path.clone(),
+    }
+}
+
+// Renders the spans as an indented tree. Sibling spans with the same name
+// (e.g. one per input line) are merged into a single row with a count.
+fn timing_tree(records: &[SpanRecord]) -> String {
+    let roots: Vec<usize> = (0..records.len())
+        .filter(|index| records[*index].parent.is_none())
+        .collect();
+
+    let mut output = String::new();
+    for group in group_by_name(records, &roots) {
+        write_group(records, &group, 0, &mut output);
+    }
+    output
+}
+
+fn write_group(records: &[SpanRecord], group: &[usize], depth: usize, output: &mut String) {
+    let name = records[group[0]].name;
+    let total: Duration = group.iter().map(|index| records[*index].duration).sum();
+
+    let label = format!("{}{}", "  ".repeat(depth), name);
+    if group.len() > 1 {
+        output.push_str(&format!(
+            "{:<30} {:>12?} (x{})\n",
+            label,
+            total,
+            group.len()
+        ));
+    } else {
+        output.push_str(&format!("{:<30} {:>12?}\n", label, total));
+    }
+
+    let children: Vec<usize> = (0..records.len())
+        .filter(|index| {
+            records[*index]
+                .parent
+                .is_some_and(|parent| group.contains(&parent))
+        })
+        .collect();
+    for child_group in group_by_name(records, &children) {
+        write_group(records, &child_group, depth + 1, output);
+    }
+}
+
+// Groups span indexes by name, keeping the order each name was first seen in.
+fn group_by_name(records: &[SpanRecord], indexes: &[usize]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for index in indexes {
+        match groups
+            .iter_mut()
+            .find(|group| records[group[0]].name == records[*index].name)
+        {
+            Some(group) => group.push(*index),
+            None => groups.push(vec![*index]),
+        }
+    }
+    groups
+}
+
+// Chrome trace-event JSON, as read by chrome://tracing or Perfetto.
+// Each span is a "complete" (ph: X) event, with times in microseconds.
+fn chrome_trace(records: &[SpanRecord]) -> String {
+    let events: Vec<String> = records
+        .iter()
+        .map(|record| {
+            format!(
+                "{{\"name\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1}}",
+                record.name.replace('\\', "\\\\").replace('"', "\\\""),
+                record.start.as_micros(),
+                record.duration.as_micros()
+            )
+        })
+        .collect();
+
+    format!("{{\"traceEvents\":[{}]}}\n", events.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{args, parse_flags};
+    use crate::timing::{
+        chrome_trace, options_from_flags, time_span, timing_tree, SpanRecord, TimingOptions,
+        TIMINGS, TIMING_ENABLED,
+    };
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    fn record(name: &'static str, parent: Option<usize>, start: u64, duration: u64) -> SpanRecord {
+        SpanRecord {
+            name,
+            parent,
+            start: Duration::from_micros(start),
+            duration: Duration::from_micros(duration),
+        }
+    }
+
+    #[test]
+    fn test_options_from_flags() {
+        let options = |values: &[&str]| options_from_flags(&parse_flags(args(values)));
+
+        assert_eq!(options(&[]), TimingOptions::default());
+        assert_eq!(
+            options(&["-v", "--timings", "--trace", "out.json"]),
+            TimingOptions {
+                print_tree: true,
+                trace_path: Some("out.json".to_string()),
+            }
+        );
+        assert_eq!(options(&["--trace", "-q"]), TimingOptions::default());
+    }
+
+    #[test]
+    fn test_spans_nest_under_the_open_span() {
+        // Spans are kept per thread, so this only sees its own spans. Timing itself is
+        // enabled for the whole process, so turn it back off however the test ends.
+        struct DisableTiming;
+        impl Drop for DisableTiming {
+            fn drop(&mut self) {
+                TIMING_ENABLED.store(false, Ordering::Relaxed);
+            }
+        }
+        let _disable_timing = DisableTiming;
+        TIMING_ENABLED.store(true, Ordering::Relaxed);
+
+        let parse = time_span("parse");
+        drop(parse);
+
+        let part = time_span("part 2");
+        let first_pass = time_span("first pass");
+        let inner = time_span("inner");
+        drop(inner);
+        drop(first_pass);
+        let second_pass = time_span("second pass");
+        drop(second_pass);
+        drop(part);
+
+        let after = time_span("after");
+        drop(after);
+
+        TIMINGS.with(|timings| {
+            let timings = timings.borrow();
+            let spans: Vec<(&str, Option<usize>)> = timings
+                .records
+                .iter()
+                .map(|record| (record.name, record.parent))
+                .collect();
+
+            assert_eq!(
+                spans,
+                vec![
+                    ("parse", None),
+                    ("part 2", None),
+                    ("first pass", Some(1)),
+                    ("inner", Some(2)),
+                    ("second pass", Some(1)),
+                    ("after", None),
+                ]
+            );
+            assert!(timings.open_spans.is_empty());
+            // A parent is open for at least as long as its children.
+            assert!(timings.records[1].duration >= timings.records[2].duration);
+            assert!(timings.records[2].duration >= timings.records[3].duration);
+        });
+    }
+
+    #[test]
+    fn test_tree_merges_repeated_siblings() {
+        let records = vec![
+            record("parse", None, 0, 10),
+            record("part 2", None, 10, 100),
+            record("first pass", Some(1), 10, 20),
+            record("second pass", Some(1), 30, 30),
+            record("first pass", Some(1), 60, 20),
+            record("second pass", Some(1), 80, 30),
+        ];
+
+        let tree = timing_tree(&records);
+        let lines: Vec<&str> = tree.lines().map(|line| line.trim_end()).collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("parse ") && lines[0].ends_with("10µs"));
+        assert!(lines[1].starts_with("part 2 ") && lines[1].ends_with("100µs"));
+        assert!(lines[2].starts_with("  first pass ") && lines[2].ends_with("40µs (x2)"));
+        assert!(lines[3].starts_with("  second pass ") && lines[3].ends_with("60µs (x2)"));
+    }
+
+    #[test]
+    fn test_chrome_trace_events() {
+        let records = vec![
+            record("parse", None, 5, 10),
+            record("part \"1\"", None, 15, 20),
+        ];
+
+        assert_eq!(
+            chrome_trace(&records),
+            "{\"traceEvents\":[\
+             {\"name\":\"parse\",\"ph\":\"X\",\"ts\":5,\"dur\":10,\"pid\":1,\"tid\":1},\
+             {\"name\":\"part \\\"1\\\"\",\"ph\":\"X\",\"ts\":15,\"dur\":20,\"pid\":1,\"tid\":1}\
+             ]}\n"
+        );
+    }
+}